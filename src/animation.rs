@@ -0,0 +1,125 @@
+use raylib::{
+    color::Color,
+    math::{Rectangle, Vector2},
+    prelude::RaylibDraw,
+    texture::Texture2D,
+};
+
+use crate::SCALE;
+
+static FRAME_SIZE: f32 = 16.0; // frames are laid out side by side in a single row
+static WALK_THRESHOLD: f32 = 0.5; // horizontal speed below which the player counts as standing
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum AnimationState {
+    Idle,
+    Walk,
+    Jump,
+    Fall,
+    Land,
+    Grow,
+}
+
+impl AnimationState {
+    /// First frame in the sheet, number of frames, ticks per frame and whether it loops.
+    fn frames(&self) -> (i32, i32, i32, bool) {
+        match self {
+            AnimationState::Idle => (0, 2, 30, true),
+            AnimationState::Walk => (2, 4, 6, true),
+            AnimationState::Jump => (6, 1, 1, true),
+            AnimationState::Fall => (7, 1, 1, true),
+            AnimationState::Land => (8, 2, 4, false),
+            AnimationState::Grow => (10, 2, 4, true),
+        }
+    }
+}
+
+pub struct Animator {
+    sheet: Texture2D,
+    state: AnimationState,
+    tick: i32,
+    facing_left: bool,
+}
+
+impl Animator {
+    pub fn new(sheet: Texture2D) -> Self {
+        Self {
+            sheet,
+            state: AnimationState::Idle,
+            tick: 0,
+            facing_left: false,
+        }
+    }
+
+    /// Picks the animation for this tick from how the player is moving.
+    pub fn update(&mut self, velocity: Vector2, on_ground: bool, scaling: bool) {
+        if velocity.x > WALK_THRESHOLD {
+            self.facing_left = false;
+        } else if velocity.x < -WALK_THRESHOLD {
+            self.facing_left = true;
+        }
+
+        let state = if scaling {
+            AnimationState::Grow
+        } else if !on_ground {
+            if velocity.y < 0.0 {
+                AnimationState::Jump
+            } else {
+                AnimationState::Fall
+            }
+        } else if matches!(self.state, AnimationState::Jump | AnimationState::Fall)
+            || (self.state == AnimationState::Land && !self.finished())
+        {
+            AnimationState::Land
+        } else if velocity.x.abs() > WALK_THRESHOLD {
+            AnimationState::Walk
+        } else {
+            AnimationState::Idle
+        };
+
+        if state != self.state {
+            self.state = state;
+            self.tick = 0;
+        } else {
+            self.tick += 1;
+        }
+    }
+
+    /// Forgets the current animation, e.g. after a respawn.
+    pub fn reset(&mut self) {
+        self.state = AnimationState::Idle;
+        self.tick = 0;
+    }
+
+    fn finished(&self) -> bool {
+        let (_, count, ticks_per_frame, looping) = self.state.frames();
+        !looping && self.tick >= count * ticks_per_frame - 1
+    }
+
+    fn frame(&self) -> i32 {
+        let (first, count, ticks_per_frame, looping) = self.state.frames();
+        let frame = self.tick / ticks_per_frame;
+        if looping {
+            first + frame % count
+        } else {
+            first + frame.min(count - 1)
+        }
+    }
+
+    pub fn render(&self, display: &mut impl RaylibDraw, position: Vector2, scale: f32) {
+        let width = if self.facing_left {
+            -FRAME_SIZE // a negative source width mirrors the frame
+        } else {
+            FRAME_SIZE
+        };
+        let size = FRAME_SIZE * scale * SCALE as f32;
+        display.draw_texture_pro(
+            &self.sheet,
+            Rectangle::new(self.frame() as f32 * FRAME_SIZE, 0.0, width, FRAME_SIZE),
+            Rectangle::new(position.x, position.y, size, size),
+            Vector2::zero(),
+            0.0,
+            Color::WHITE,
+        );
+    }
+}
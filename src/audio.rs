@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use raylib::{
+    audio::{Music, RaylibAudio, Sound},
+    prelude::RaylibDrawHandle,
+};
+
+use crate::{config, event::GameEvent, settings::Settings};
+
+static CROSSFADE_TICKS: i32 = 90;
+
+/// Name of the sound bank entry played for an event, if it makes a sound.
+fn sound_name(event: &GameEvent) -> Option<&'static str> {
+    let name = match event {
+        GameEvent::Jumped => "jump",
+        GameEvent::PlayerLanded { .. } => "land",
+        GameEvent::Grew => "grow",
+        GameEvent::Shrank => "shrink",
+        GameEvent::LeverFlipped { .. } => "lever_flip",
+        GameEvent::BombPickedUp { .. } => "bomb_pickup",
+        GameEvent::BombPlaced { .. } => "bomb_place",
+        GameEvent::BombExploded { .. } => "explosion",
+        GameEvent::TileDestroyed { .. } => "wall_break",
+        GameEvent::LevelCompleted => "exit_reached",
+        GameEvent::PlayerDied => return None,
+    };
+    Some(name)
+}
+
+struct BankSound<'aud> {
+    sound: Sound<'aud>,
+    pitch_variation: f32, // pitch is picked at random from 1 +- this
+    cooldown: i32,        // minimum ticks between two plays
+    last_played: Option<i32>,
+}
+
+struct Track<'aud> {
+    path: String,
+    music: Music<'aud>,
+}
+
+/// Owns all music and sound effects and mixes them through the master, music and SFX
+/// volume buses from the settings.
+pub struct AudioManager<'aud> {
+    audio: &'aud RaylibAudio,
+    music_volume: f32,
+    sfx_volume: f32,
+    music: Option<Track<'aud>>,
+    fading: Option<Track<'aud>>, // the previous track while it crossfades out
+    fade_tick: i32,
+    sounds: HashMap<String, BankSound<'aud>>,
+    ticks: i32,
+}
+
+impl<'aud> AudioManager<'aud> {
+    pub fn new(audio: &'aud RaylibAudio, settings: &Settings) -> Self {
+        audio.set_master_volume(settings.master_volume);
+        Self {
+            audio,
+            music_volume: settings.music_volume,
+            sfx_volume: settings.sfx_volume,
+            music: None,
+            fading: None,
+            fade_tick: CROSSFADE_TICKS,
+            sounds: HashMap::new(),
+            ticks: 0,
+        }
+    }
+
+    /// Picks up volume changes, the music follows on the next `update`.
+    pub fn apply_settings(&mut self, settings: &Settings) {
+        self.audio.set_master_volume(settings.master_volume);
+        self.music_volume = settings.music_volume;
+        self.sfx_volume = settings.sfx_volume;
+    }
+
+    /// Loads the sound bank, a config file of `name = path, pitch variation, cooldown` lines.
+    pub fn load_sound_bank(&mut self, path: &str) {
+        for (name, value) in config::load(path) {
+            let fields: Vec<&str> = value.split(',').map(|field| field.trim()).collect();
+            let (sound_path, pitch_variation, cooldown) = match fields[..] {
+                [sound_path] => (sound_path, Ok(0.0), Ok(0)),
+                [sound_path, pitch] => (sound_path, pitch.parse(), Ok(0)),
+                [sound_path, pitch, cooldown] => (sound_path, pitch.parse(), cooldown.parse()),
+                _ => {
+                    println!("Invalid sound bank entry for {name}: {value}");
+                    continue;
+                }
+            };
+            let (Ok(pitch_variation), Ok(cooldown)) = (pitch_variation, cooldown) else {
+                println!("Invalid sound bank entry for {name}: {value}");
+                continue;
+            };
+            match self.audio.new_sound(sound_path) {
+                Ok(sound) => {
+                    self.sounds.insert(
+                        name,
+                        BankSound {
+                            sound,
+                            pitch_variation,
+                            cooldown,
+                            last_played: None,
+                        },
+                    );
+                }
+                Err(err) => println!("Could not load sound {sound_path}: {err}"),
+            }
+        }
+    }
+
+    /// Plays the sounds for everything that happened since the last call. Sounds still
+    /// cooling down are skipped, so e.g. scaling doesn't retrigger its sound every tick.
+    pub fn play_events(&mut self, d: &RaylibDrawHandle, events: &[GameEvent]) {
+        for event in events {
+            let Some(bank_sound) = sound_name(event).and_then(|name| self.sounds.get_mut(name))
+            else {
+                continue;
+            };
+            if bank_sound
+                .last_played
+                .is_some_and(|tick| self.ticks - tick < bank_sound.cooldown)
+            {
+                continue;
+            }
+            let jitter = d.get_random_value::<i32>(-100..100) as f32 / 100.0;
+            bank_sound
+                .sound
+                .set_pitch(1.0 + jitter * bank_sound.pitch_variation);
+            bank_sound.sound.set_volume(self.sfx_volume);
+            bank_sound.sound.play();
+            bank_sound.last_played = Some(self.ticks);
+        }
+    }
+
+    /// Switches to the given track, crossfading from the one currently playing.
+    /// Does nothing if the track is already playing.
+    pub fn play_music(&mut self, path: &str) {
+        if self.music.as_ref().is_some_and(|track| track.path == path) {
+            return;
+        }
+        let mut music = match self.audio.new_music(path) {
+            Ok(music) => music,
+            Err(err) => {
+                println!("Could not load music {path}: {err}");
+                return;
+            }
+        };
+        music.set_volume(0.0);
+        music.play_stream();
+        self.fading = self.music.take();
+        self.fade_tick = 0;
+        self.music = Some(Track {
+            path: path.to_string(),
+            music,
+        });
+    }
+
+    /// Keeps the music streams fed, has to be called every frame.
+    pub fn update(&mut self) {
+        self.ticks += 1;
+        self.fade_tick = (self.fade_tick + 1).min(CROSSFADE_TICKS);
+        let progress = self.fade_tick as f32 / CROSSFADE_TICKS as f32;
+        if let Some(track) = &mut self.music {
+            track.music.set_volume(self.music_volume * progress);
+            track.music.update_stream();
+        }
+        if let Some(track) = &mut self.fading {
+            track.music.set_volume(self.music_volume * (1.0 - progress));
+            track.music.update_stream();
+        }
+        if progress >= 1.0 {
+            self.fading = None;
+        }
+    }
+}
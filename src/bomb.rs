@@ -0,0 +1,220 @@
+use raylib::{
+    color::Color,
+    math::Vector2,
+    prelude::RaylibDraw,
+    text::{RaylibFont, WeakFont},
+};
+
+use crate::{
+    event::GameEvent, explosion::Explosion, kill_player, player::Player, GameState, Vector2i,
+    TILE_SIZE_PIXELS,
+};
+
+static CHAIN_DELAY: i32 = 8; // ticks between a blast and the bombs it sets off
+static KNOCKBACK_RANGE: f32 = 2.5; // in tiles, the push fades out towards this distance
+static FUSE_TICKS: i32 = 180;
+static FUSE_BOMB: usize = 21; // explodes on its own instead of waiting for a remote detonation
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum BlastShape {
+    Square,
+    Cross,
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Clone, Copy)]
+pub struct Blast {
+    pub shape: BlastShape,
+    pub radius: i32,
+    pub knockback: f32,
+    pub lethal_radius: Option<f32>, // in tiles, from the center of the bomb
+}
+
+impl Blast {
+    /// The blast of the bomb with the given tile id, if it is a bomb at all.
+    pub fn for_tile(id: usize) -> Option<Self> {
+        let (shape, radius, knockback, lethal_radius) = match id {
+            8 => (BlastShape::Square, 1, 60.0, None),
+            18 => (BlastShape::Cross, 2, 80.0, Some(0.5)),
+            19 => (BlastShape::Horizontal, 3, 80.0, Some(0.5)),
+            20 => (BlastShape::Vertical, 3, 80.0, Some(0.5)),
+            21 => (BlastShape::Square, 1, 60.0, None),
+            _ => return None,
+        };
+        Some(Self {
+            shape,
+            radius,
+            knockback,
+            lethal_radius,
+        })
+    }
+
+    pub fn tiles(&self, center: &Vector2i) -> Vec<Vector2i> {
+        let mut tiles = Vec::new();
+        for dx in -self.radius..=self.radius {
+            for dy in -self.radius..=self.radius {
+                let hit = match self.shape {
+                    BlastShape::Square => true,
+                    BlastShape::Cross => dx == 0 || dy == 0,
+                    BlastShape::Horizontal => dy == 0,
+                    BlastShape::Vertical => dx == 0,
+                };
+                if hit {
+                    tiles.push(Vector2i::new(center.x + dx, center.y + dy));
+                }
+            }
+        }
+        tiles
+    }
+}
+
+pub fn is_bomb(id: usize) -> bool {
+    Blast::for_tile(id).is_some()
+}
+
+/// A bomb that is going to explode once its delay runs out.
+pub struct Detonation {
+    pub position: Vector2i,
+    pub delay: i32,
+}
+
+/// Lights the fuse of timed bombs, everything else waits for a remote detonation.
+pub fn arm_bomb(game_state: &mut GameState, pos: Vector2i, id: usize) {
+    if id == FUSE_BOMB {
+        game_state.detonations.push(Detonation {
+            position: pos,
+            delay: FUSE_TICKS,
+        });
+    } else {
+        game_state.placed_bombs.push(pos);
+    }
+}
+
+/// Takes a bomb back off the map so it won't go off.
+pub fn disarm_bomb(game_state: &mut GameState, pos: &Vector2i) {
+    game_state.placed_bombs.retain(|placed| placed != pos);
+    game_state
+        .detonations
+        .retain(|detonation| detonation.position != *pos);
+}
+
+/// Detonates the oldest placed bomb, so bombs can be set off one by one in placement order.
+pub fn detonate_next_bomb(game_state: &mut GameState) {
+    if game_state.placed_bombs.is_empty() {
+        return;
+    }
+    let pos = game_state.placed_bombs.remove(0);
+    game_state.detonations.push(Detonation {
+        position: pos,
+        delay: 0,
+    });
+}
+
+pub fn detonate_all_bombs(game_state: &mut GameState) {
+    for pos in game_state.placed_bombs.drain(..) {
+        game_state.detonations.push(Detonation {
+            position: pos,
+            delay: 0,
+        });
+    }
+}
+
+/// Advances pending detonations and sets off the ones that are due.
+pub fn update_detonations(
+    game_state: &mut GameState,
+    player: &mut Player,
+    explosions: &mut Vec<Explosion>,
+) {
+    let mut due = Vec::new();
+    game_state.detonations.retain_mut(|detonation| {
+        detonation.delay -= 1;
+        if detonation.delay < 0 {
+            due.push(detonation.position.clone());
+            return false;
+        }
+        true
+    });
+
+    for bomb_pos in due {
+        let blast = match game_state.current_level.tilemap.get_tile(&bomb_pos) {
+            Some(tile) => Blast::for_tile(tile.id()),
+            None => None,
+        };
+        let Some(blast) = blast else {
+            continue; // already blown up by another bomb
+        };
+        game_state.events.push(GameEvent::BombExploded {
+            position: bomb_pos.clone(),
+        });
+        game_state
+            .current_level
+            .tilemap
+            .set_tile(bomb_pos.clone(), 0);
+        explosions.push(Explosion::new(bomb_pos.clone()));
+
+        let origin = Vector2::new(
+            (bomb_pos.x as f32 + 0.5) * TILE_SIZE_PIXELS as f32,
+            (bomb_pos.y as f32 + 0.5) * TILE_SIZE_PIXELS as f32,
+        );
+        let offset = player.center() - origin;
+        let distance = offset.length() / TILE_SIZE_PIXELS as f32;
+        if blast.lethal_radius.is_some_and(|radius| distance <= radius) {
+            kill_player(game_state, player);
+        } else if distance < KNOCKBACK_RANGE {
+            player.velocity +=
+                offset.normalized() * blast.knockback * (1.0 - distance / KNOCKBACK_RANGE);
+        }
+
+        for pos in blast.tiles(&bomb_pos) {
+            let Some(tile) = game_state.current_level.tilemap.get_tile(&pos) else {
+                continue;
+            };
+            if tile.id() == 5 {
+                game_state.current_level.tilemap.set_tile(pos.clone(), 0);
+                game_state
+                    .events
+                    .push(GameEvent::TileDestroyed { position: pos });
+            } else if is_bomb(tile.id()) {
+                // chain reaction, placed or not
+                game_state.placed_bombs.retain(|placed| *placed != pos);
+                if let Some(detonation) = game_state
+                    .detonations
+                    .iter_mut()
+                    .find(|detonation| detonation.position == pos)
+                {
+                    // cut burning fuses short
+                    detonation.delay = detonation.delay.min(CHAIN_DELAY);
+                } else {
+                    game_state.detonations.push(Detonation {
+                        position: pos,
+                        delay: CHAIN_DELAY,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Draws the seconds left on every burning fuse.
+/// `font` is the default font, which the world camera's draw handle can't get by itself.
+pub fn render_fuses(d: &mut impl RaylibDraw, font: &WeakFont, game_state: &GameState) {
+    for detonation in game_state.detonations.iter() {
+        let tile = game_state
+            .current_level
+            .tilemap
+            .get_tile(&detonation.position);
+        if tile.is_none() || tile.unwrap().id() != FUSE_BOMB {
+            continue;
+        }
+        let text = format!("{}", detonation.delay / 60 + 1);
+        let width = font.measure_text(&text, 32.0, 3.0).x as i32; // the spacing `draw_text` uses
+        d.draw_text(
+            &text,
+            (detonation.position.x * TILE_SIZE_PIXELS) + TILE_SIZE_PIXELS / 2 - width / 2,
+            (detonation.position.y * TILE_SIZE_PIXELS) + TILE_SIZE_PIXELS / 2 - 16,
+            32,
+            Color::RED,
+        );
+    }
+}
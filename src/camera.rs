@@ -0,0 +1,89 @@
+use raylib::{
+    camera::Camera2D,
+    color::Color,
+    math::Vector2,
+    prelude::{RaylibDraw, RaylibDrawHandle},
+};
+
+use crate::event::GameEvent;
+
+static MAX_SHAKE: f32 = 24.0; // in pixels, at full trauma
+static MAX_ROLL: f32 = 3.0; // in degrees, at full trauma
+static TRAUMA_DECAY: f32 = 0.03; // per tick
+static FLASH_DECAY: f32 = 0.08;
+static PUNCH_DECAY: f32 = 0.85; // the zoom punch is multiplied by this every tick
+static HEAVY_LANDING_SCALE: f32 = 1.5; // landings from this size up shake the screen
+
+/// Screen shake, flashes and zoom punches. Shake is driven by trauma, which events add to
+/// and which wears off over time; the offset grows with the square of the trauma so small
+/// hits stay subtle.
+#[derive(Default)]
+pub struct CameraEffects {
+    trauma: f32,
+    flash: f32,
+    punch: f32,
+    shake: Vector2,
+    roll: f32,
+}
+
+impl CameraEffects {
+    pub fn handle_events(&mut self, events: &[GameEvent]) {
+        for event in events {
+            match event {
+                GameEvent::BombExploded { .. } => {
+                    self.trauma += 0.5;
+                    self.flash = 0.6;
+                    self.punch = 0.05;
+                }
+                GameEvent::PlayerLanded { scale, .. } if *scale >= HEAVY_LANDING_SCALE => {
+                    self.trauma += (scale - 1.0) * 0.3;
+                }
+                _ => {}
+            }
+        }
+        self.trauma = self.trauma.min(1.0);
+    }
+
+    pub fn update(&mut self, d: &RaylibDrawHandle) {
+        let random = || d.get_random_value::<i32>(-100..100) as f32 / 100.0;
+        let shake = self.trauma * self.trauma;
+        self.shake = Vector2::new(random(), random()) * MAX_SHAKE * shake;
+        self.roll = random() * MAX_ROLL * shake;
+
+        self.trauma = (self.trauma - TRAUMA_DECAY).max(0.0);
+        self.flash = (self.flash - FLASH_DECAY).max(0.0);
+        self.punch *= PUNCH_DECAY;
+    }
+
+    /// The shaken and zoomed camera to draw the world through. With effects turned off it
+    /// leaves the world where it is.
+    pub fn camera(&self, d: &RaylibDrawHandle, enabled: bool) -> Camera2D {
+        let center = Vector2::new(
+            d.get_screen_width() as f32 / 2.0,
+            d.get_screen_height() as f32 / 2.0,
+        );
+        if !enabled {
+            return Camera2D {
+                offset: center,
+                target: center,
+                rotation: 0.0,
+                zoom: 1.0,
+            };
+        }
+        Camera2D {
+            offset: center + self.shake,
+            target: center,
+            rotation: self.roll,
+            zoom: 1.0 + self.punch,
+        }
+    }
+
+    /// Draws the flash in screen space, on top of the world.
+    pub fn render_flash(&self, d: &mut RaylibDrawHandle, enabled: bool) {
+        if enabled && self.flash > 0.0 {
+            let width = d.get_screen_width();
+            let height = d.get_screen_height();
+            d.draw_rectangle(0, 0, width, height, Color::WHITE.alpha(self.flash));
+        }
+    }
+}
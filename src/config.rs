@@ -0,0 +1,46 @@
+use std::{collections::HashMap, fmt::Display, fs, str::FromStr};
+
+/// Reads a file of `key = value` lines. Blank lines and lines starting with `#` are skipped.
+/// A missing file gives an empty map so the caller can fall back to its defaults.
+pub fn load(path: &str) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    let Ok(contents) = fs::read_to_string(path) else {
+        println!("Could not read {path}, using defaults");
+        return values;
+    };
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.split_once('=') {
+            Some((key, value)) => {
+                values.insert(key.trim().to_string(), value.trim().to_string());
+            }
+            None => println!("{path}:{}: expected `key = value`", number + 1),
+        }
+    }
+    values
+}
+
+/// Overwrites `field` with the parsed value of `key`, if it is there and valid.
+pub fn read<T: FromStr>(values: &HashMap<String, String>, key: &str, field: &mut T) {
+    let Some(value) = values.get(key) else {
+        return;
+    };
+    match value.parse() {
+        Ok(parsed) => *field = parsed,
+        Err(_) => println!("Invalid value for {key}: {value}"),
+    }
+}
+
+/// Writes `key = value` lines that `load` can read back.
+pub fn save(path: &str, values: &[(&str, &dyn Display)]) {
+    let contents: String = values
+        .iter()
+        .map(|(key, value)| format!("{key} = {value}\n"))
+        .collect();
+    if let Err(err) = fs::write(path, contents) {
+        println!("Could not save {path}: {err}");
+    }
+}
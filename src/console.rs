@@ -0,0 +1,145 @@
+use std::str::FromStr;
+
+use raylib::{
+    color::Color,
+    consts::KeyboardKey,
+    prelude::{RaylibDraw, RaylibDrawHandle},
+    RaylibHandle,
+};
+
+use crate::Vector2i;
+
+static FONT_SIZE: i32 = 20;
+static LINE_HEIGHT: i32 = 24;
+static MAX_LINES: usize = 10; // lines of output kept above the input
+
+static HELP: &[&str] = &[
+    "level <n>           jump to a level",
+    "give bombs <n>      add bombs to the inventory, up to 99",
+    "noclip              fly through walls, W A S D to move",
+    "scale <f>           resize the player within the level's range",
+    "tile <x> <y> <id>   set a tile",
+    "flip <x> <y>        fire what a lever or plate is wired to",
+    "restart             restart the level",
+];
+
+pub enum Command {
+    Level(i32),
+    GiveBombs(usize),
+    Noclip,
+    Scale(f32),
+    Tile(Vector2i, usize),
+    Flip(Vector2i),
+    Restart,
+}
+
+/// A drop-down console for running commands against the live game, opened with the key
+/// left of 1. The game is paused while it is open.
+pub struct Console {
+    pub open: bool,
+    input: String,
+    output: Vec<String>,
+}
+
+impl Default for Console {
+    fn default() -> Self {
+        Self {
+            open: false,
+            input: String::new(),
+            output: vec!["Type help for a list of commands".to_string()],
+        }
+    }
+}
+
+impl Console {
+    /// Handles typing, returns the command entered this frame, if any. Takes the
+    /// `RaylibHandle` because typed characters can't be read while drawing.
+    pub fn update(&mut self, rl: &mut RaylibHandle) -> Option<Command> {
+        if rl.is_key_pressed(KeyboardKey::KEY_GRAVE) {
+            self.open = !self.open;
+            return None;
+        }
+        if !self.open {
+            return None;
+        }
+
+        while let Some(c) = rl.get_char_pressed() {
+            if c != '`' {
+                self.input.push(c);
+            }
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) {
+            self.input.pop();
+        }
+        if !rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+            return None;
+        }
+
+        let line = std::mem::take(&mut self.input);
+        self.print(format!("> {line}"));
+        if line.trim() == "help" {
+            for help in HELP {
+                self.print(help.to_string());
+            }
+            return None;
+        }
+        match parse(&line) {
+            Ok(command) => Some(command),
+            Err(err) => {
+                self.print(err);
+                None
+            }
+        }
+    }
+
+    pub fn print(&mut self, line: String) {
+        self.output.push(line);
+        if self.output.len() > MAX_LINES {
+            self.output.remove(0);
+        }
+    }
+
+    pub fn render(&self, d: &mut RaylibDrawHandle) {
+        let height = (MAX_LINES as i32 + 1) * LINE_HEIGHT + 16;
+        d.draw_rectangle(0, 0, d.get_screen_width(), height, Color::BLACK.alpha(0.8));
+        for (i, line) in self.output.iter().enumerate() {
+            d.draw_text(
+                line,
+                8,
+                8 + i as i32 * LINE_HEIGHT,
+                FONT_SIZE,
+                Color::LIGHTGRAY,
+            );
+        }
+        // blinking cursor
+        let cursor = if d.get_time() % 1.0 < 0.5 { "_" } else { "" };
+        d.draw_text(
+            &format!("> {}{cursor}", self.input),
+            8,
+            8 + MAX_LINES as i32 * LINE_HEIGHT,
+            FONT_SIZE,
+            Color::WHITE,
+        );
+    }
+}
+
+fn parse(line: &str) -> Result<Command, String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    match words[..] {
+        ["level", n] => Ok(Command::Level(number(n)?)),
+        ["give", "bombs", n] => Ok(Command::GiveBombs(number(n)?)),
+        ["noclip"] => Ok(Command::Noclip),
+        ["scale", scale] => Ok(Command::Scale(number(scale)?)),
+        ["tile", x, y, id] => Ok(Command::Tile(
+            Vector2i::new(number(x)?, number(y)?),
+            number(id)?,
+        )),
+        ["flip", x, y] => Ok(Command::Flip(Vector2i::new(number(x)?, number(y)?))),
+        ["restart"] => Ok(Command::Restart),
+        _ => Err(format!("Unknown command: {line}")),
+    }
+}
+
+fn number<T: FromStr>(word: &str) -> Result<T, String> {
+    word.parse().map_err(|_| format!("Not a number: {word}"))
+}
@@ -0,0 +1,118 @@
+use raylib::{
+    color::Color,
+    math::{Rectangle, Vector2},
+    prelude::{RaylibDraw, RaylibDrawHandle},
+};
+
+use crate::{player::Player, tile::Collision, GameState, Vector2i, TILE_SIZE_PIXELS};
+
+static FONT_SIZE: i32 = 20;
+static MARGIN: i32 = 16;
+static VELOCITY_SCALE: f32 = 4.0; // velocity arrows are this many ticks of movement long
+
+/// Shows what the physics see: collision outlines, the player's hitbox, tile and velocity,
+/// placed bombs and the frame timing. Toggled with F3.
+pub fn render_debug(d: &mut RaylibDrawHandle, game_state: &GameState, player: &Player) {
+    render_collision(d, game_state, player);
+    render_bombs(d, game_state);
+    render_player(d, player);
+    render_stats(d, player);
+}
+
+/// Outlines the tiles `Tilemap::collides` treats as solid for the player at their current
+/// size, one way vents that only block against their direction in orange.
+fn render_collision(d: &mut RaylibDrawHandle, game_state: &GameState, player: &Player) {
+    for (pos, tile) in game_state.current_level.tilemap.iter() {
+        let collision = tile.collision();
+        let color = if collision.blocks(player.scale(), Vector2::zero()) {
+            Color::RED
+        } else if matches!(collision, Collision::OneWay(_)) {
+            Color::ORANGE
+        } else {
+            continue;
+        };
+        d.draw_rectangle_lines_ex(tile_rect(pos), 1.0, color);
+    }
+}
+
+fn render_bombs(d: &mut RaylibDrawHandle, game_state: &GameState) {
+    for pos in game_state.placed_bombs.iter() {
+        let rect = tile_rect(pos);
+        d.draw_circle_lines(
+            (rect.x + rect.width / 2.0) as i32,
+            (rect.y + rect.height / 2.0) as i32,
+            TILE_SIZE_PIXELS as f32 / 2.0,
+            Color::YELLOW,
+        );
+    }
+    for detonation in game_state.detonations.iter() {
+        let rect = tile_rect(&detonation.position);
+        d.draw_rectangle_lines_ex(rect, 2.0, Color::YELLOW);
+        d.draw_text(
+            &detonation.delay.to_string(),
+            rect.x as i32 + 4,
+            rect.y as i32 + 4,
+            FONT_SIZE,
+            Color::YELLOW,
+        );
+    }
+}
+
+fn render_player(d: &mut RaylibDrawHandle, player: &Player) {
+    d.draw_rectangle_lines_ex(tile_rect(&player.tile_from_center()), 2.0, Color::SKYBLUE);
+    d.draw_rectangle_lines_ex(player.hitbox(), 2.0, Color::LIME);
+    let center = player.center();
+    let tip = center + player.velocity * VELOCITY_SCALE;
+    d.draw_line_ex(center, tip, 2.0, Color::MAGENTA);
+    d.draw_circle_v(tip, 4.0, Color::MAGENTA);
+}
+
+/// Lists the numbers in the bottom left corner, clear of the HUD.
+fn render_stats(d: &mut RaylibDrawHandle, player: &Player) {
+    let tile = player.tile_from_center();
+    let lines = [
+        format!(
+            "FPS {}   FRAME {:.2} MS",
+            d.get_fps(),
+            d.get_frame_time() * 1000.0
+        ),
+        format!(
+            "POSITION {:.1}, {:.1}   TILE {tile}",
+            player.position.x, player.position.y
+        ),
+        format!(
+            "VELOCITY {:.2}, {:.2}",
+            player.velocity.x, player.velocity.y
+        ),
+        format!(
+            "ON GROUND {}   TICKS SINCE GROUND {}",
+            player.on_ground, player.tick_since_last_ground
+        ),
+    ];
+    let top = d.get_screen_height() - MARGIN - lines.len() as i32 * (FONT_SIZE + 4);
+    d.draw_rectangle(
+        0,
+        top - 8,
+        d.get_screen_width() / 2,
+        d.get_screen_height() - top + 8,
+        Color::BLACK.alpha(0.5),
+    );
+    for (i, line) in lines.iter().enumerate() {
+        d.draw_text(
+            line,
+            MARGIN,
+            top + i as i32 * (FONT_SIZE + 4),
+            FONT_SIZE,
+            Color::WHITE,
+        );
+    }
+}
+
+fn tile_rect(pos: &Vector2i) -> Rectangle {
+    Rectangle::new(
+        (pos.x * TILE_SIZE_PIXELS) as f32,
+        (pos.y * TILE_SIZE_PIXELS) as f32,
+        TILE_SIZE_PIXELS as f32,
+        TILE_SIZE_PIXELS as f32,
+    )
+}
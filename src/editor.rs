@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+
+use raylib::{
+    color::Color,
+    consts::{KeyboardKey, MouseButton},
+    math::{Rectangle, Vector2},
+    prelude::{RaylibDraw, RaylibDrawHandle},
+};
+
+use crate::{
+    level::{Level, Wire},
+    tile::Collision,
+    Vector2i, TILE_SIZE_PIXELS,
+};
+
+static PALETTE_ICON_SIZE: i32 = 32;
+static SPAWN: usize = 9;
+
+/// Paints tiles with the mouse and wires levers and pressure plates to the tiles they change.
+///
+/// Left click paints the selected tile, right click picks the tile under the mouse, the
+/// mouse wheel or the palette along the bottom changes the selection. W on a lever starts
+/// wiring it: left clicks then make the clicked tile turn into the selected one when the
+/// lever is flipped (toggling back and forth with shift held), right clicks remove wires,
+/// W again stops. H hides the palette to reach the bottom row. Ctrl+S saves the level.
+pub struct Editor {
+    pub enabled: bool, // saving overwrites the shipped levels, so players don't get the editor
+    pub active: bool,
+    selected: usize,
+    wiring: Option<Vector2i>, // the lever whose wires are being edited
+    show_palette: bool,
+}
+
+impl Editor {
+    pub fn new(enabled: bool, active: bool) -> Self {
+        Self {
+            enabled,
+            active: enabled && active,
+            selected: 1,
+            wiring: None,
+            show_palette: true,
+        }
+    }
+
+    /// Handles the editor input and draws its overlay on top of the level.
+    pub fn update(
+        &mut self,
+        d: &mut RaylibDrawHandle,
+        level: &mut Level,
+        tileset: &HashMap<u32, (&str, Collision, usize)>,
+    ) {
+        let tile_count = tileset.len();
+        let mouse = d.get_mouse_position();
+        if d.is_key_pressed(KeyboardKey::KEY_H) {
+            self.show_palette = !self.show_palette;
+        }
+        // the palette covers the bottom half of the last row while it is shown
+        let palette_top = if self.show_palette {
+            d.get_screen_height() - PALETTE_ICON_SIZE
+        } else {
+            d.get_screen_height()
+        };
+        let hovered = Vector2i::new(
+            mouse.x as i32 / TILE_SIZE_PIXELS,
+            mouse.y as i32 / TILE_SIZE_PIXELS,
+        );
+
+        let wheel = d.get_mouse_wheel_move();
+        if wheel > 0.0 {
+            self.selected = (self.selected + 1) % tile_count;
+        } else if wheel < 0.0 {
+            self.selected = (self.selected + tile_count - 1) % tile_count;
+        }
+
+        if d.is_key_pressed(KeyboardKey::KEY_W) {
+            let on_lever = level
+                .tilemap
+                .get_tile(&hovered)
+                .is_some_and(|tile| matches!(tile.id(), 6 | 7 | 27 | 28));
+            self.wiring = match self.wiring {
+                None if on_lever => Some(hovered.clone()),
+                _ => None,
+            };
+        }
+
+        if mouse.y as i32 >= palette_top {
+            if d.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
+                let id = (mouse.x as i32 / PALETTE_ICON_SIZE) as usize;
+                if id < tile_count {
+                    self.selected = id;
+                }
+            }
+        } else if let Some(lever) = self.wiring.clone() {
+            if d.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) && hovered != lever {
+                let toggle_back = if d.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) {
+                    level.tilemap.get_tile(&hovered).map(|tile| tile.id())
+                } else {
+                    None
+                };
+                let wires = level.wires.entry(lever).or_default();
+                wires.retain(|wire| wire.target != hovered);
+                wires.push(Wire {
+                    target: hovered.clone(),
+                    id: self.selected,
+                    toggle_back,
+                });
+            }
+            if d.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_RIGHT) {
+                if let Some(wires) = level.wires.get_mut(&lever) {
+                    wires.retain(|wire| wire.target != hovered);
+                }
+            }
+        } else {
+            if d.is_mouse_button_down(MouseButton::MOUSE_BUTTON_LEFT) {
+                self.paint(level, hovered.clone());
+            }
+            if d.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_RIGHT) {
+                if let Some(tile) = level.tilemap.get_tile(&hovered) {
+                    self.selected = tile.id();
+                }
+            }
+        }
+
+        if self.enabled
+            && d.is_key_down(KeyboardKey::KEY_LEFT_CONTROL)
+            && d.is_key_pressed(KeyboardKey::KEY_S)
+        {
+            level.save(tileset);
+        }
+
+        self.render(d, level, &hovered, palette_top);
+    }
+
+    fn paint(&self, level: &mut Level, pos: Vector2i) {
+        if level.tilemap.get_tile(&pos).is_none() {
+            return; // outside the map
+        }
+        if self.selected == SPAWN {
+            // there can only be one spawn
+            let spawns: Vec<Vector2i> = level
+                .tilemap
+                .iter()
+                .filter(|(_pos, tile)| tile.id() == SPAWN)
+                .map(|(pos, _tile)| pos.clone())
+                .collect();
+            for spawn in spawns {
+                level.tilemap.set_tile(spawn, 0);
+            }
+        }
+        level.tilemap.set_tile(pos, self.selected);
+    }
+
+    fn render(
+        &self,
+        d: &mut RaylibDrawHandle,
+        level: &Level,
+        hovered: &Vector2i,
+        palette_top: i32,
+    ) {
+        let tile_center = |pos: &Vector2i| {
+            Vector2::new(
+                (pos.x as f32 + 0.5) * TILE_SIZE_PIXELS as f32,
+                (pos.y as f32 + 0.5) * TILE_SIZE_PIXELS as f32,
+            )
+        };
+
+        // wires of the lever being edited stand out, the others are only hinted at
+        for (lever, wires) in level.wires.iter() {
+            let color = if self.wiring.as_ref() == Some(lever) {
+                Color::YELLOW
+            } else {
+                Color::YELLOW.alpha(0.3)
+            };
+            for wire in wires {
+                d.draw_line_ex(tile_center(lever), tile_center(&wire.target), 3.0, color);
+                if let Some(tile) = level.tilemap.tile(wire.id) {
+                    let size = TILE_SIZE_PIXELS as f32 / 2.0;
+                    d.draw_texture_ex(
+                        tile.texture(),
+                        tile_center(&wire.target) - Vector2::new(size / 2.0, size / 2.0),
+                        0.0,
+                        size / tile.texture().width as f32,
+                        color,
+                    );
+                }
+            }
+        }
+
+        d.draw_rectangle_lines_ex(
+            Rectangle::new(
+                (hovered.x * TILE_SIZE_PIXELS) as f32,
+                (hovered.y * TILE_SIZE_PIXELS) as f32,
+                TILE_SIZE_PIXELS as f32,
+                TILE_SIZE_PIXELS as f32,
+            ),
+            2.0,
+            Color::WHITE,
+        );
+
+        if self.show_palette {
+            self.render_palette(d, level, palette_top);
+        }
+
+        let text = if self.wiring.is_some() {
+            "WIRING  CLICK: ADD  SHIFT+CLICK: TOGGLE  RIGHT CLICK: REMOVE  W: DONE"
+        } else {
+            "EDITOR  F2: PLAY  W: WIRE LEVER  H: PALETTE  CTRL+S: SAVE"
+        };
+        d.draw_text(text, 8, 8, 20, Color::WHITE);
+    }
+
+    fn render_palette(&self, d: &mut RaylibDrawHandle, level: &Level, palette_top: i32) {
+        d.draw_rectangle(
+            0,
+            palette_top,
+            d.get_screen_width(),
+            PALETTE_ICON_SIZE,
+            Color::BLACK.alpha(0.7),
+        );
+        let mut id = 0;
+        while let Some(tile) = level.tilemap.tile(id) {
+            let x = id as i32 * PALETTE_ICON_SIZE;
+            d.draw_texture_ex(
+                tile.texture(),
+                Vector2::new(x as f32, palette_top as f32),
+                0.0,
+                PALETTE_ICON_SIZE as f32 / tile.texture().width as f32,
+                Color::WHITE,
+            );
+            if id == self.selected {
+                d.draw_rectangle_lines(
+                    x,
+                    palette_top,
+                    PALETTE_ICON_SIZE,
+                    PALETTE_ICON_SIZE,
+                    Color::YELLOW,
+                );
+            }
+            id += 1;
+        }
+    }
+}
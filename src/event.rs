@@ -0,0 +1,58 @@
+use raylib::{math::Vector2, prelude::RaylibDrawHandle};
+
+use crate::{
+    audio::AudioManager,
+    camera::CameraEffects,
+    particle::Particles,
+    transition::{LevelStats, Transition},
+    GameState, Vector2i,
+};
+
+/// Something that happened in the simulation this frame. Gameplay code pushes these into
+/// `GameState::events` instead of reacting itself, and `dispatch_events` hands them to
+/// everything that cares.
+#[derive(Clone)]
+pub enum GameEvent {
+    Jumped,
+    PlayerLanded { feet: Vector2, scale: f32 },
+    Grew,
+    Shrank,
+    PlayerDied,
+    LeverFlipped { x: i32, y: i32 }, // also sent by pressure plates
+    BombPickedUp { position: Vector2i },
+    BombPlaced { position: Vector2i },
+    BombExploded { position: Vector2i },
+    TileDestroyed { position: Vector2i },
+    LevelCompleted,
+}
+
+/// Hands the events queued this frame to the level scripts, the stats, the audio, the
+/// particles and the camera.
+pub fn dispatch_events(
+    game_state: &mut GameState,
+    audio: &mut AudioManager,
+    particles: &mut Particles,
+    camera: &mut CameraEffects,
+    d: &RaylibDrawHandle,
+) {
+    let events = std::mem::take(&mut game_state.events);
+    for event in events.iter() {
+        match event {
+            GameEvent::LeverFlipped { x, y } => game_state.current_level.on_lever_flip(*x, *y),
+            GameEvent::BombPlaced { .. } => game_state.bombs_used += 1,
+            GameEvent::PlayerDied => game_state.deaths += 1,
+            GameEvent::LevelCompleted => {
+                game_state.transition = Some(Transition::new(LevelStats {
+                    level_number: game_state.level_number,
+                    time: game_state.level_time,
+                    bombs_used: game_state.bombs_used,
+                    deaths: game_state.deaths - game_state.level_start_deaths,
+                }));
+            }
+            _ => {}
+        }
+    }
+    audio.play_events(d, &events);
+    particles.handle_events(d, &events);
+    camera.handle_events(&events);
+}
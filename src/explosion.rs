@@ -1,9 +1,4 @@
-use raylib::{
-    color::Color,
-    math::Vector2,
-    prelude::{RaylibDraw, RaylibDrawHandle},
-    texture::Texture2D,
-};
+use raylib::{color::Color, math::Vector2, prelude::RaylibDraw, texture::Texture2D};
 
 use crate::{Vector2i, TILE_SIZE_PIXELS};
 
@@ -17,7 +12,7 @@ impl Explosion {
         Self { position, timer: 0 }
     }
 
-    pub fn render(&mut self, d: &mut RaylibDrawHandle, textures: &Vec<Texture2D>) {
+    pub fn render(&mut self, d: &mut impl RaylibDraw, textures: &Vec<Texture2D>) {
         if self.finished() {
             return;
         }
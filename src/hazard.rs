@@ -0,0 +1,45 @@
+use crate::{player::Player, GameState, Vector2i};
+
+static LASER_PERIOD: i32 = 90; // ticks a laser stays on or off
+static CRUSHER_PERIOD: i32 = 120; // ticks a crusher stays extended or retracted
+
+pub fn is_lethal(id: usize) -> bool {
+    match id {
+        22 => true, // spikes
+        23 => true, // laser on
+        25 => true, // crusher extended
+        _ => false,
+    }
+}
+
+/// Switches lasers and crushers on their timers.
+pub fn update_hazards(game_state: &mut GameState) {
+    game_state.ticks += 1;
+    let toggle_lasers = game_state.ticks % LASER_PERIOD == 0;
+    let toggle_crushers = game_state.ticks % CRUSHER_PERIOD == 0;
+    if !toggle_lasers && !toggle_crushers {
+        return;
+    }
+
+    let mut changes: Vec<(Vector2i, usize)> = Vec::new();
+    for (pos, tile) in game_state.current_level.tilemap.iter() {
+        match tile.id() {
+            23 if toggle_lasers => changes.push((pos.clone(), 24)),
+            24 if toggle_lasers => changes.push((pos.clone(), 23)),
+            25 if toggle_crushers => changes.push((pos.clone(), 26)),
+            26 if toggle_crushers => changes.push((pos.clone(), 25)),
+            _ => {}
+        }
+    }
+    for (pos, id) in changes {
+        game_state.current_level.tilemap.set_tile(pos, id);
+    }
+}
+
+pub fn touches_hazard(game_state: &GameState, player: &Player) -> bool {
+    game_state
+        .current_level
+        .tilemap
+        .overlapping(&player.hitbox())
+        .any(|(_pos, tile)| is_lethal(tile.id()))
+}
@@ -0,0 +1,144 @@
+use raylib::{
+    color::Color,
+    math::Vector2,
+    prelude::{RaylibDraw, RaylibDrawHandle},
+};
+
+use crate::{bomb::is_bomb, player::Player, GameState, TILE_SIZE_PIXELS};
+
+static METER_WIDTH: i32 = 16;
+static METER_HEIGHT: i32 = 128;
+static MARGIN: i32 = 16;
+static ICON_SIZE: i32 = 32;
+static FONT_SIZE: i32 = 20;
+
+pub fn render_hud(d: &mut RaylibDrawHandle, game_state: &GameState, player: &Player) {
+    render_size_meter(d, game_state, player);
+    render_bombs(d, game_state);
+    render_level_info(d, game_state);
+    render_prompt(d, game_state, player);
+}
+
+/// Draws the held bombs in the top left corner, the next one to be placed last.
+fn render_bombs(d: &mut RaylibDrawHandle, game_state: &GameState) {
+    for (i, bomb) in game_state.bombs.iter().enumerate() {
+        if let Some(tile) = game_state.current_level.tilemap.tile(*bomb) {
+            d.draw_texture_ex(
+                tile.texture(),
+                Vector2::new((MARGIN + i as i32 * ICON_SIZE) as f32, MARGIN as f32),
+                0.0,
+                ICON_SIZE as f32 / tile.texture().width as f32,
+                Color::WHITE,
+            );
+        }
+    }
+    let armed = game_state.placed_bombs.len() + game_state.detonations.len();
+    if armed > 0 {
+        d.draw_text(
+            &format!("ARMED: {armed}"),
+            MARGIN,
+            MARGIN + ICON_SIZE + 8,
+            FONT_SIZE,
+            Color::WHITE,
+        );
+    }
+}
+
+fn render_level_info(d: &mut RaylibDrawHandle, game_state: &GameState) {
+    let seconds = game_state.level_time as i32;
+    let text = format!(
+        "LEVEL {}   {:02}:{:02}",
+        game_state.level_number,
+        seconds / 60,
+        seconds % 60
+    );
+    let width = d.measure_text(&text, FONT_SIZE);
+    d.draw_text(
+        &text,
+        d.get_screen_width() / 2 - width / 2,
+        MARGIN,
+        FONT_SIZE,
+        Color::WHITE,
+    );
+}
+
+/// Tells the player what pressing F would do on the tile they're standing on.
+fn render_prompt(d: &mut RaylibDrawHandle, game_state: &GameState, player: &Player) {
+    if player.is_dead() {
+        return;
+    }
+    let Some(tile) = game_state
+        .current_level
+        .tilemap
+        .get_tile(&player.tile_from_center())
+    else {
+        return;
+    };
+    let action = match tile.id() {
+        6 | 7 => "FLIP LEVER",
+        14 => "SALVAGE",
+        3 => "SET CHECKPOINT",
+        0 if !game_state.bombs.is_empty() => "PLACE BOMB",
+        id if is_bomb(id) => "PICK UP BOMB",
+        _ => return,
+    };
+    let text = format!("PRESS F TO {action}");
+    let width = d.measure_text(&text, FONT_SIZE);
+    let center = player.center();
+    d.draw_text(
+        &text,
+        center.x as i32 - width / 2,
+        player.position.y as i32 - TILE_SIZE_PIXELS / 2,
+        FONT_SIZE,
+        Color::WHITE,
+    );
+}
+
+/// Draws a vertical meter in the top right corner showing the player's size
+/// within the range the current level allows.
+fn render_size_meter(d: &mut RaylibDrawHandle, game_state: &GameState, player: &Player) {
+    let min_scale = game_state.current_level.min_scale;
+    let max_scale = game_state.current_level.max_scale;
+    let x = d.get_screen_width() - MARGIN - METER_WIDTH;
+    let y = MARGIN;
+    let meter_y = |scale: f32| {
+        y + METER_HEIGHT
+            - ((scale - min_scale) / (max_scale - min_scale) * METER_HEIGHT as f32) as i32
+    };
+
+    d.draw_rectangle(x, y, METER_WIDTH, METER_HEIGHT, Color::BLACK.alpha(0.5));
+    let fill = meter_y(player.scale());
+    d.draw_rectangle(
+        x,
+        fill,
+        METER_WIDTH,
+        y + METER_HEIGHT - fill,
+        Color::LIGHTGRAY,
+    );
+    if min_scale < 1.0 && max_scale > 1.0 {
+        // mark the biggest size that still fits through vents
+        let vent = meter_y(1.0);
+        d.draw_line(x - 4, vent, x + METER_WIDTH + 4, vent, Color::SKYBLUE);
+    }
+    d.draw_rectangle_lines(x, y, METER_WIDTH, METER_HEIGHT, Color::WHITE);
+
+    let text = format!("x{:.2}", player.scale());
+    let width = d.measure_text(&text, FONT_SIZE);
+    d.draw_text(
+        &text,
+        x + METER_WIDTH - width,
+        y + METER_HEIGHT + 8,
+        20,
+        Color::WHITE,
+    );
+    if player.snap_scaling {
+        let width = d.measure_text("SNAP", FONT_SIZE);
+        d.draw_text(
+            "SNAP",
+            x + METER_WIDTH - width,
+            y + METER_HEIGHT + 32,
+            FONT_SIZE,
+            Color::WHITE,
+        );
+    }
+}
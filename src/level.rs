@@ -1,13 +1,72 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
 
-use image::GenericImageView;
 use raylib::{RaylibHandle, RaylibThread};
 
-use crate::{tile::Tile, tilemap::Tilemap, Vector2i};
+use crate::{
+    config,
+    tile::{Collision, Tile},
+    tilemap::{decode_tiles, Tilemap},
+    Vector2i,
+};
+
+/// What happens to the bombs the player is holding when they enter a level.
+#[derive(Clone)]
+pub enum Inventory {
+    Carry,             // keep whatever was left from the previous level
+    Fixed(Vec<usize>), // start with exactly these bomb tile ids, an empty list resets
+}
+
+/// Connects a lever or pressure plate to a tile it changes.
+#[derive(Clone)]
+pub struct Wire {
+    pub target: Vector2i,
+    pub id: usize,                  // tile id the target is set to
+    pub toggle_back: Option<usize>, // if set, flipping again while the target is `id` sets this
+}
+
+impl Display for Wire {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.target.x, self.target.y, self.id)?;
+        if let Some(back) = self.toggle_back {
+            write!(f, "/{back}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Wire {
+    /// Parses `x y id` or `x y id/toggle_back`.
+    fn parse(text: &str) -> Option<Self> {
+        let mut fields = text.split_whitespace();
+        let x = fields.next()?.parse().ok()?;
+        let y = fields.next()?.parse().ok()?;
+        let id = fields.next()?;
+        let (id, toggle_back) = match id.split_once('/') {
+            Some((id, back)) => (id.parse().ok()?, Some(back.parse().ok()?)),
+            None => (id.parse().ok()?, None),
+        };
+        Some(Self {
+            target: Vector2i::new(x, y),
+            id,
+            toggle_back,
+        })
+    }
+}
 
 pub struct Level {
-    pub tilemap: Tilemap,
-    lever_hook: Option<Box<dyn FnMut(&mut Self, i32, i32)>>,
+    pub background: Option<Tilemap>, // decoration drawn behind the player
+    pub tilemap: Tilemap,            // the layer the player collides and interacts with
+    pub foreground: Option<Tilemap>, // decoration drawn in front of the player
+    pub min_scale: f32,
+    pub max_scale: f32,
+    pub inventory: Inventory,
+    pub music: &'static str, // track that plays while the level is on screen
+    pub wires: HashMap<Vector2i, Vec<Wire>>, // by the position of the lever or plate
+    pub path: String,
 }
 
 impl Level {
@@ -15,57 +74,201 @@ impl Level {
         rl: &mut RaylibHandle,
         thread: &RaylibThread,
         path: &str,
-        tileset: HashMap<u32, (&str, bool, usize)>,
-        lever_trigger: Option<Box<dyn FnMut(&mut Self, i32, i32)>>,
+        tileset: HashMap<u32, (&str, Collision, usize)>,
     ) -> Self {
         Self {
-            tilemap: load_tilemap(rl, thread, path, tileset),
-            lever_hook: lever_trigger,
+            background: load_layer(rl, thread, path, "bg", &tileset),
+            tilemap: load_tilemap(rl, thread, path, &tileset, false),
+            foreground: load_layer(rl, thread, path, "fg", &tileset),
+            min_scale: 1.0,
+            max_scale: 2.0,
+            inventory: Inventory::Carry,
+            music: "assets/music.wav",
+            wires: load_wires(path),
+            path: path.to_string(),
         }
     }
 
+    pub fn with_scale_range(mut self, min_scale: f32, max_scale: f32) -> Self {
+        self.min_scale = min_scale;
+        self.max_scale = max_scale;
+        self
+    }
+
+    pub fn with_inventory(mut self, inventory: Inventory) -> Self {
+        self.inventory = inventory;
+        self
+    }
+
+    pub fn with_music(mut self, music: &'static str) -> Self {
+        self.music = music;
+        self
+    }
+
+    /// The bombs the player starts this level with, given what they're carrying in.
+    pub fn starting_bombs(&self, carried: &[usize]) -> Vec<usize> {
+        match &self.inventory {
+            Inventory::Carry => carried.to_vec(),
+            Inventory::Fixed(bombs) => bombs.clone(),
+        }
+    }
+
+    /// Loads the layers, their textures and the wires again from disk. Keeps what was set
+    /// with the builder methods, drops every change made to the tiles since loading.
+    pub fn reload(
+        &mut self,
+        rl: &mut RaylibHandle,
+        thread: &RaylibThread,
+        tileset: HashMap<u32, (&str, Collision, usize)>,
+    ) {
+        let reloaded = Self::load_from_file(rl, thread, &self.path, tileset);
+        self.background = reloaded.background;
+        self.tilemap = reloaded.tilemap;
+        self.foreground = reloaded.foreground;
+        self.wires = reloaded.wires;
+    }
+
+    /// The files the level is loaded from: the image, its decoration layers and the sidecar.
+    pub fn files(&self) -> Vec<PathBuf> {
+        vec![
+            PathBuf::from(&self.path),
+            layer_path(&self.path, "bg"),
+            layer_path(&self.path, "fg"),
+            PathBuf::from(wires_path(&self.path)),
+        ]
+    }
+
+    pub fn restart(&mut self) {
+        self.tilemap.reset();
+    }
+
     pub fn on_lever_flip(&mut self, x: i32, y: i32) {
-        if let Some(mut hook) = self.lever_hook.take() {
-            hook(self, x, y);
-            self.lever_hook = Some(hook);
+        let Some(wires) = self.wires.get(&Vector2i::new(x, y)) else {
+            return;
+        };
+        for wire in wires {
+            let current = self.tilemap.get_tile(&wire.target).map(|tile| tile.id());
+            let id = match wire.toggle_back {
+                Some(back) if current == Some(wire.id) => back,
+                _ => wire.id,
+            };
+            self.tilemap.set_tile(wire.target.clone(), id);
+        }
+    }
+
+    /// Writes the layers back to the level PNGs and the wires to the sidecar.
+    pub fn save(&self, tileset: &HashMap<u32, (&str, Collision, usize)>) {
+        self.tilemap.save(&self.path, tileset);
+        if let Some(background) = &self.background {
+            background.save(&layer_path(&self.path, "bg").to_string_lossy(), tileset);
+        }
+        if let Some(foreground) = &self.foreground {
+            foreground.save(&layer_path(&self.path, "fg").to_string_lossy(), tileset);
+        }
+
+        // in reading order, so saving again doesn't shuffle the file
+        let mut levers: Vec<_> = self
+            .wires
+            .iter()
+            .filter(|(_lever, wires)| !wires.is_empty())
+            .collect();
+        levers.sort_by_key(|(lever, _wires)| (lever.y, lever.x));
+        let lines: Vec<(String, String)> = levers
+            .into_iter()
+            .map(|(lever, wires)| {
+                let wires: Vec<String> = wires.iter().map(|wire| wire.to_string()).collect();
+                (format!("lever {} {}", lever.x, lever.y), wires.join(", "))
+            })
+            .collect();
+        let values: Vec<(&str, &dyn Display)> = lines
+            .iter()
+            .map(|(key, value)| (key.as_str(), value as &dyn Display))
+            .collect();
+        config::save(&wires_path(&self.path), &values);
+    }
+}
+
+/// The sidecar next to the level PNG, e.g. `level1.cfg`, holds what its levers do.
+fn wires_path(path: &str) -> String {
+    Path::new(path)
+        .with_extension("cfg")
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Reads `lever x y = target, target, ...` lines, see `Wire::parse` for the targets.
+fn load_wires(path: &str) -> HashMap<Vector2i, Vec<Wire>> {
+    let path = wires_path(path);
+    let mut wires = HashMap::new();
+    if !Path::new(&path).exists() {
+        return wires; // levels without levers don't need a sidecar
+    }
+    for (key, value) in config::load(&path) {
+        let lever: Vec<i32> = key
+            .split_whitespace()
+            .skip(1)
+            .filter_map(|field| field.parse().ok())
+            .collect();
+        let targets: Option<Vec<Wire>> = value.split(',').map(Wire::parse).collect();
+        match (key.starts_with("lever "), &lever[..], targets) {
+            (true, [x, y], Some(targets)) => {
+                wires.insert(Vector2i::new(*x, *y), targets);
+            }
+            _ => println!("Invalid wiring in {path}: {key} = {value}"),
         }
     }
+    wires
+}
+
+/// Decoration layers are stored next to the level, e.g. `level1_fg.png`.
+fn layer_path(path: &str, suffix: &str) -> PathBuf {
+    let path = Path::new(path);
+    path.with_file_name(format!(
+        "{}_{suffix}.png",
+        path.file_stem().unwrap().to_string_lossy()
+    ))
+}
+
+/// Loads an optional decoration layer, `None` if the level has none.
+fn load_layer(
+    rl: &mut RaylibHandle,
+    thread: &RaylibThread,
+    path: &str,
+    suffix: &str,
+    tileset: &HashMap<u32, (&str, Collision, usize)>,
+) -> Option<Tilemap> {
+    let layer_path = layer_path(path, suffix);
+    if !layer_path.exists() {
+        return None;
+    }
+    Some(load_tilemap(
+        rl,
+        thread,
+        &layer_path.to_string_lossy(),
+        tileset,
+        true,
+    ))
 }
 
 fn load_tilemap(
     rl: &mut RaylibHandle,
     thread: &RaylibThread,
     path: &str,
-    tileset: HashMap<u32, (&str, bool, usize)>,
+    tileset: &HashMap<u32, (&str, Collision, usize)>,
+    decoration: bool, // decoration layers leave transparent pixels empty
 ) -> Tilemap {
     let mut tiles = Vec::new();
     let mut sorted: Vec<_> = tileset.values().clone().collect();
     sorted.sort_by_key(|k| k.2);
-    for (tile_path, solid, id) in sorted.iter() {
-        tiles.push(Tile::new(rl, thread, tile_path, *solid, *id));
+    for (tile_path, collision, id) in sorted.iter() {
+        tiles.push(Tile::new(rl, thread, tile_path, *collision, *id));
     }
 
     let mut tilemap = Tilemap::new(tiles);
-    let level_image = image::open(path);
-    for pixel in level_image.unwrap().pixels() {
-        let r = pixel.2 .0[0] as u32;
-        let g = pixel.2 .0[1] as u32;
-        let b = pixel.2 .0[2] as u32;
-        let a = pixel.2 .0[3];
-
-        let code = r << 16 | g << 8 | b;
-
-        let tile_id = tileset.get(&code);
-        if tile_id.is_some() && a == 255 {
-            let tile_data = tile_id.unwrap();
-            tilemap.set_tile(
-                Vector2i::new(pixel.0 as i32, pixel.1 as i32),
-                tile_data.2, // the id
-            );
-        } else {
-            println!("Unknown color code: {code}");
-            tilemap.set_tile(Vector2i::new(pixel.0 as i32, pixel.1 as i32), 0);
-        }
+    let level_image = image::open(path).unwrap().to_rgba8();
+    for (pos, id) in decode_tiles(&level_image, tileset, decoration) {
+        tilemap.set_tile(pos, id);
     }
+    tilemap.save_initial_state();
     return tilemap;
 }
@@ -1,22 +1,59 @@
-use std::{collections::HashMap, fmt::Display};
+use std::{collections::HashMap, fmt::Display, path::Path};
 
+use audio::AudioManager;
+use bomb::{
+    arm_bomb, detonate_all_bombs, detonate_next_bomb, disarm_bomb, is_bomb, render_fuses,
+    update_detonations, Detonation,
+};
+use camera::CameraEffects;
+use console::{Command, Console};
+use debug::render_debug;
+use editor::Editor;
+use event::{dispatch_events, GameEvent};
 use explosion::Explosion;
-use level::Level;
+use hazard::{touches_hazard, update_hazards};
+use hud::render_hud;
+use level::{Inventory, Level};
 use math::Vector2;
+use movement::MovementConfig;
+use particle::Particles;
 use player::Player;
 use raylib::prelude::*;
+use settings::Settings;
+use tile::{Collision, Direction::*};
+use transition::Transition;
+use watcher::AssetWatcher;
+use weight::{update_fragile_floors, update_pressure_plates};
 
+mod animation;
+mod audio;
+mod bomb;
+mod camera;
+mod config;
+mod console;
+mod debug;
+mod editor;
+mod event;
 mod explosion;
+mod hazard;
+mod hud;
 mod level;
+mod movement;
+mod particle;
 mod player;
+mod settings;
 mod tile;
 mod tilemap;
+mod transition;
 mod utils;
+mod watcher;
+mod weight;
 
 static SCALE: i32 = 4;
 static TILEMAP_WIDTH: i32 = 16;
 static TILEMAP_HEIGHT: i32 = 10;
 static TILE_SIZE_PIXELS: i32 = 16 * SCALE;
+static MAX_BOMBS: usize = 99; // most bombs the console hands out
 
 #[derive(Eq, PartialEq, Hash, Clone)]
 pub struct Vector2i {
@@ -38,8 +75,20 @@ impl Vector2i {
 
 struct GameState {
     current_level: Level,
-    bombs: i32,
+    bombs: Vec<usize>, // tile ids of the bombs held, the last one is placed first
+    level_start_bombs: Vec<usize>, // what the player brought into the level
     placed_bombs: Vec<Vector2i>,
+    detonations: Vec<Detonation>,
+    crumbling: Vec<(Vector2i, i32)>, // cracked floors and the ticks until they give way
+    checkpoint: Option<Vector2i>,    // respawn position, overrides the level spawn
+    level_number: i32,
+    level_time: f32, // seconds spent in the current level
+    bombs_used: i32, // armed in the current level, counting restarts
+    deaths: i32,
+    level_start_deaths: i32,
+    ticks: i32,                     // drives the hazard timers
+    transition: Option<Transition>, // set while moving on to the next level
+    events: Vec<GameEvent>,         // what happened this frame, see `dispatch_events`
     won: bool,
 }
 
@@ -55,118 +104,106 @@ fn main() {
     rl.set_exit_key(None);
     rl.set_target_fps(60);
 
-    let player_texture = rl.load_texture(&thread, "assets/player.png").unwrap();
-    let mut player = Player::new(Vector2::zero(), player_texture);
-
-    let mut levels: Vec<Level> = Vec::new();
-    let tileset = HashMap::from([
-        (0xFFFFFF, ("assets/background.png", false, 0)),
-        (0x0, ("assets/wall.png", true, 1)),
-        (0x143c96, ("assets/vent.png", false, 2)),
-        (0x14a064, ("assets/dead_robot.png", false, 3)),
-        (0xff0000, ("assets/exit.png", false, 4)),
-        (0x5a5a5a, ("assets/weak_wall.png", true, 5)),
-        (0xff00f0, ("assets/lever_off.png", false, 6)),
-        (0xff00ff, ("assets/lever_on.png", false, 7)),
-        (0x00ff00, ("assets/bomb.png", false, 8)),
-        (0x146464, ("assets/background.png", false, 9)), // player spawn
-    ]);
-
-    levels.push(Level::load_from_file(
-        &mut rl,
-        &thread,
-        "assets/level1.png",
-        tileset.clone(),
-        Some(Box::new(|level, _x, _y| {
-            level.tilemap.set_tile(Vector2i::new(13, 8), 0)
-        })),
-    ));
-
-    levels.push(Level::load_from_file(
-        &mut rl,
-        &thread,
-        "assets/level2.png",
-        tileset.clone(),
-        None,
-    ));
-
-    levels.push(Level::load_from_file(
-        &mut rl,
-        &thread,
-        "assets/level3.png",
-        tileset.clone(),
-        Some(Box::new(|level, _x, _y| {
-            level.tilemap.set_tile(Vector2i::new(6, 1), 0);
-            level.tilemap.set_tile(Vector2i::new(6, 2), 0);
-        })),
-    ));
-
-    levels.push(Level::load_from_file(
-        &mut rl,
-        &thread,
-        "assets/level4.png",
-        tileset.clone(),
-        Some(Box::new(|level, x, y| match (x, y) {
-            (7, 8) => {
-                if level.tilemap.get_tile(&Vector2i::new(5, 5)).unwrap().id() == 1 {
-                    level.tilemap.set_tile(Vector2i::new(5, 5), 0);
-                    level.tilemap.set_tile(Vector2i::new(12, 1), 1);
-                } else {
-                    level.tilemap.set_tile(Vector2i::new(5, 5), 1);
-                    level.tilemap.set_tile(Vector2i::new(12, 1), 0);
-                }
-            }
-            (13, 8) => {
-                level.tilemap.set_tile(Vector2i::new(1, 5), 0);
-            }
-            (1, 1) => {
-                level.tilemap.set_tile(Vector2i::new(11, 1), 0);
-            }
-            _ => {}
-        })),
-    ));
-
-    levels.push(Level::load_from_file(
-        &mut rl,
-        &thread,
-        "assets/level5.png",
-        tileset.clone(),
-        Some(Box::new(|level, x, y| match (x, y) {
-            (5, 2) => {
-                level.tilemap.set_tile(Vector2i::new(2, 3), 0);
-            }
-            (9, 3) => {
-                level.tilemap.set_tile(Vector2i::new(1, 5), 0);
-            }
-            (6, 6) => {
-                level.tilemap.set_tile(Vector2i::new(9, 5), 0);
-            }
-            (14, 3) => {
-                level.tilemap.set_tile(Vector2i::new(1, 8), 8);
-            }
-            _ => {}
-        })),
-    ));
+    let player_sheet = rl.load_texture(&thread, "assets/player_sheet.png").unwrap();
+    let dead_player_texture = rl.load_texture(&thread, "assets/dead_robot.png").unwrap();
+    let mut player = Player::new(Vector2::zero(), player_sheet, dead_player_texture)
+        .with_movement(MovementConfig::load("assets/movement.cfg"));
+
+    let tileset = tileset();
 
+    let mut levels = load_levels(&mut rl, &thread, &tileset);
+    let first_level = levels.remove(0);
+    let starting_bombs = first_level.starting_bombs(&[]);
     let mut game_state = GameState {
-        current_level: levels.remove(0),
-        bombs: 0,
+        current_level: first_level,
+        bombs: starting_bombs.clone(),
+        level_start_bombs: starting_bombs,
         placed_bombs: Vec::new(),
+        detonations: Vec::new(),
+        crumbling: Vec::new(),
+        checkpoint: None,
+        level_number: 1,
+        level_time: 0.0,
+        bombs_used: 0,
+        deaths: 0,
+        level_start_deaths: 0,
+        ticks: 0,
+        transition: None,
+        events: Vec::new(),
         won: false,
     };
 
     set_player_pos(&game_state, &mut player);
-    let mut started = false;
+    // `--edit` skips the title screen and opens the first level in the editor
+    let edit = std::env::args().any(|arg| arg == "--edit");
+    // `--dev` reloads the current level or config whenever one of their files changes
+    let dev = std::env::args().any(|arg| arg == "--dev");
+    // F2 switches to the editor only with either of them
+    let mut editor = Editor::new(edit || dev, edit);
+    let mut started = editor.active;
+    let mut watcher = dev.then(|| AssetWatcher::new("assets")).flatten();
+    let mut show_debug = false;
+    let mut console = Console::default();
     let mut explosions: Vec<Explosion> = Vec::new();
+    let mut particles = Particles::load("assets/particles.cfg");
+    let mut camera = CameraEffects::default();
+    let font = rl.get_font_default();
     let explosion_textures = vec![
         rl.load_texture(&thread, "assets/explosion1.png").unwrap(),
         rl.load_texture(&thread, "assets/explosion2.png").unwrap(),
         rl.load_texture(&thread, "assets/explosion3.png").unwrap(),
     ];
-    let audio = RaylibAudio::init_audio_device().unwrap();
-    let music = audio.new_sound("assets/music.wav").unwrap();
-    let bomb_sfx = audio.new_sound("assets/explosion.wav").unwrap();
+    let audio_device = RaylibAudio::init_audio_device().unwrap();
+    let mut settings = Settings::load();
+    let mut audio = AudioManager::new(&audio_device, &settings);
+    audio.load_sound_bank("assets/sounds.cfg");
+    if started {
+        audio.play_music(game_state.current_level.music);
+    }
     while !rl.window_should_close() {
+        if let Some(watcher) = &mut watcher {
+            let level_files = game_state.current_level.files();
+            let mut reload = false;
+            for path in watcher.changed() {
+                if path == Path::new("assets/movement.cfg") {
+                    println!("Reloading {}", path.display());
+                    player.set_movement(MovementConfig::load("assets/movement.cfg"));
+                } else if path == Path::new("assets/sounds.cfg") {
+                    println!("Reloading {}", path.display());
+                    audio.load_sound_bank("assets/sounds.cfg");
+                } else if path == Path::new("assets/particles.cfg") {
+                    println!("Reloading {}", path.display());
+                    particles = Particles::load("assets/particles.cfg");
+                } else {
+                    // what the editor saves is already on screen
+                    reload |= !editor.active
+                        && (level_files.contains(&path)
+                            || tileset
+                                .values()
+                                .any(|(texture, ..)| path == Path::new(texture)));
+                }
+            }
+            if reload {
+                println!("Reloading {}", game_state.current_level.path);
+                reload_level(&mut rl, &thread, &tileset, &mut game_state, &mut player);
+            }
+        }
+        if started {
+            if let Some(command) = console.update(&mut rl) {
+                let output = run_command(
+                    command,
+                    &mut rl,
+                    &thread,
+                    &tileset,
+                    &mut levels,
+                    &mut game_state,
+                    &mut player,
+                );
+                console.print(output);
+                audio.play_music(game_state.current_level.music);
+            }
+        }
+
         let mut d = rl.begin_drawing(&thread);
         d.clear_background(Color::BLACK);
 
@@ -174,12 +211,19 @@ fn main() {
             render_title_screen(&mut d);
             if d.is_key_pressed(KeyboardKey::KEY_ENTER) {
                 started = true;
+                audio.play_music(game_state.current_level.music);
             }
             continue;
         }
 
-        if !music.is_playing() {
-            music.play();
+        audio.update();
+        if console.open {
+            render_level(&mut d, &game_state, &player);
+            console.render(&mut d);
+            continue;
+        }
+        if settings.handle_keys(&d) {
+            audio.apply_settings(&settings);
         }
 
         if game_state.won {
@@ -201,13 +245,72 @@ fn main() {
                 32,
                 Color::WHITE,
             );
+            let text = format!("DEATHS: {}", game_state.deaths);
+            let width = d.measure_text(&text, 32);
+            d.draw_text(
+                &text,
+                d.get_screen_width() / 2 - width / 2,
+                d.get_screen_height() / 2 + 100,
+                32,
+                Color::WHITE,
+            );
             continue;
         }
 
-        game_state.current_level.tilemap.render(&mut d);
-        player.render(&mut d, &mut game_state);
+        if editor.enabled && d.is_key_pressed(KeyboardKey::KEY_F2) {
+            editor.active = !editor.active;
+            if !editor.active {
+                // playtest the edits from the spawn, restarts now go back to them
+                game_state.current_level.tilemap.save_initial_state();
+            }
+            game_state.transition = None;
+            restart_level(&mut game_state, &mut player);
+        }
+        if editor.active {
+            render_level(&mut d, &game_state, &player);
+            editor.update(&mut d, &mut game_state.current_level, &tileset);
+            continue;
+        }
 
-        if d.is_key_pressed(KeyboardKey::KEY_F) {
+        if game_state
+            .transition
+            .as_ref()
+            .is_some_and(|transition| transition.blocks_gameplay())
+        {
+            let mut transition = game_state.transition.take().unwrap();
+            render_level(&mut d, &game_state, &player);
+            if transition.update(&d) {
+                if levels.len() == 0 {
+                    game_state.won = true;
+                    continue;
+                }
+                enter_next_level(&mut game_state, &mut player, levels.remove(0));
+                audio.play_music(game_state.current_level.music);
+            }
+            transition.render(&mut d);
+            game_state.transition = Some(transition);
+            continue;
+        }
+
+        camera.update(&d);
+        update_hazards(&mut game_state);
+        player.update(&mut d, &mut game_state);
+        update_pressure_plates(&mut game_state, &player);
+        update_fragile_floors(&mut game_state, &player);
+        if !player.noclip && touches_hazard(&game_state, &player) {
+            kill_player(&mut game_state, &mut player);
+        }
+        if player.death_finished() {
+            respawn(&mut game_state, &mut player);
+        }
+        if d.is_key_pressed(KeyboardKey::KEY_TAB) {
+            player.snap_scaling = !player.snap_scaling;
+        }
+        if d.is_key_pressed(KeyboardKey::KEY_F3) {
+            show_debug = !show_debug;
+        }
+
+        if !player.is_dead() && d.is_key_pressed(KeyboardKey::KEY_F) {
             if game_state
                 .current_level
                 .tilemap
@@ -238,22 +341,67 @@ fn main() {
                         6
                     },
                 );
+                game_state.events.push(GameEvent::LeverFlipped {
+                    x: player.tile_from_center().x,
+                    y: player.tile_from_center().y,
+                });
+            } else if is_bomb(
                 game_state
                     .current_level
-                    .on_lever_flip(player.tile_from_center().x, player.tile_from_center().y);
+                    .tilemap
+                    .get_tile(&player.tile_from_center())
+                    .unwrap()
+                    .id(),
+            ) {
+                let bomb = game_state
+                    .current_level
+                    .tilemap
+                    .get_tile(&player.tile_from_center())
+                    .unwrap()
+                    .id();
+                game_state
+                    .current_level
+                    .tilemap
+                    .set_tile(player.tile_from_center(), 0);
+                disarm_bomb(&mut game_state, &player.tile_from_center());
+                game_state.bombs.push(bomb);
+                game_state.events.push(GameEvent::BombPickedUp {
+                    position: player.tile_from_center(),
+                });
             } else if game_state
                 .current_level
                 .tilemap
                 .get_tile(&player.tile_from_center())
                 .unwrap()
                 .id()
-                == 8
+                == 14
             {
+                // salvage a scrap robot for a bomb
                 game_state
                     .current_level
                     .tilemap
-                    .set_tile(player.tile_from_center(), 0);
-                game_state.bombs += 1;
+                    .set_tile(player.tile_from_center(), 15);
+                game_state.bombs.push(8);
+                game_state.events.push(GameEvent::BombPickedUp {
+                    position: player.tile_from_center(),
+                });
+            } else if game_state
+                .current_level
+                .tilemap
+                .get_tile(&player.tile_from_center())
+                .unwrap()
+                .id()
+                == 3
+            {
+                // reactivate a dead robot as the new checkpoint
+                if let Some(previous) = game_state.checkpoint.take() {
+                    game_state.current_level.tilemap.set_tile(previous, 3);
+                }
+                game_state
+                    .current_level
+                    .tilemap
+                    .set_tile(player.tile_from_center(), 16);
+                game_state.checkpoint = Some(player.tile_from_center());
             } else if game_state
                 .current_level
                 .tilemap
@@ -262,43 +410,168 @@ fn main() {
                 .id()
                 == 0
             {
-                if game_state.bombs >= 1 {
+                if let Some(bomb) = game_state.bombs.pop() {
                     game_state
                         .current_level
                         .tilemap
-                        .set_tile(player.tile_from_center(), 8);
-                    game_state.bombs -= 1;
-                    game_state.placed_bombs.push(player.tile_from_center());
+                        .set_tile(player.tile_from_center(), bomb);
+                    arm_bomb(&mut game_state, player.tile_from_center(), bomb);
+                    game_state.events.push(GameEvent::BombPlaced {
+                        position: player.tile_from_center(),
+                    });
                 }
             }
         } else if d.is_key_pressed(KeyboardKey::KEY_ENTER) {
-            if !game_state.placed_bombs.is_empty() {
-                bomb_sfx.play();
-            }
-            detonate_all_bombs(&mut game_state, &mut explosions);
+            detonate_all_bombs(&mut game_state);
+        } else if d.is_key_pressed(KeyboardKey::KEY_E) {
+            detonate_next_bomb(&mut game_state);
         }
 
-        for explosion in explosions.iter_mut() {
-            explosion.render(&mut d, &explosion_textures);
-        }
+        update_detonations(&mut game_state, &mut player, &mut explosions);
+        particles.update();
 
-        if game_state
-            .current_level
-            .tilemap
-            .get_tile(&player.tile_from_center())
-            .unwrap()
-            .id()
-            == 4
+        let view = camera.camera(&d, settings.screen_effects);
         {
-            // switch levels
-            if levels.len() == 0 {
-                game_state.won = true;
-                continue;
+            let mut world = d.begin_mode2D(view);
+            render_level(&mut world, &game_state, &player);
+            render_fuses(&mut world, &font, &game_state);
+            for explosion in explosions.iter_mut() {
+                explosion.render(&mut world, &explosion_textures);
+            }
+            particles.render(&mut world);
+        }
+        camera.render_flash(&mut d, settings.screen_effects);
+        game_state.level_time += d.get_frame_time();
+        render_hud(&mut d, &game_state, &player);
+        if show_debug {
+            render_debug(&mut d, &game_state, &player);
+        }
+        settings.render(&mut d);
+
+        if let Some(transition) = &mut game_state.transition {
+            // fading into the level that was just entered
+            transition.update(&d);
+            transition.render(&mut d);
+            if transition.finished() {
+                game_state.transition = None;
             }
-            game_state.current_level = levels.remove(0);
-            set_player_pos(&game_state, &mut player);
+        } else if !player.is_dead()
+            && game_state
+                .current_level
+                .tilemap
+                .get_tile(&player.tile_from_center())
+                .unwrap()
+                .id()
+                == 4
+        {
+            game_state.events.push(GameEvent::LevelCompleted);
         }
+
+        dispatch_events(&mut game_state, &mut audio, &mut particles, &mut camera, &d);
+    }
+}
+
+/// Draws the current level and the player without updating anything.
+fn render_level(d: &mut impl RaylibDraw, game_state: &GameState, player: &Player) {
+    if let Some(background) = &game_state.current_level.background {
+        background.render(d);
     }
+    let level = &game_state.current_level;
+    level.tilemap.render_over(d, level.background.as_ref());
+    player.draw(d);
+    if let Some(foreground) = &game_state.current_level.foreground {
+        foreground.render(d);
+    }
+}
+
+/// The tiles levels are made of, by the color they have in the level images, with the
+/// texture, collision and tile id of each.
+fn tileset() -> HashMap<u32, (&'static str, Collision, usize)> {
+    HashMap::from([
+        (0xFFFFFF, ("assets/background.png", Collision::None, 0)),
+        (0x0, ("assets/wall.png", Collision::Solid, 1)),
+        (0x143c96, ("assets/vent.png", Collision::Vent, 2)),
+        (0x14a064, ("assets/dead_robot.png", Collision::None, 3)),
+        (0xff0000, ("assets/exit.png", Collision::None, 4)),
+        (0x5a5a5a, ("assets/weak_wall.png", Collision::Solid, 5)),
+        (0xff00f0, ("assets/lever_off.png", Collision::None, 6)),
+        (0xff00ff, ("assets/lever_on.png", Collision::None, 7)),
+        (0x00ff00, ("assets/bomb.png", Collision::None, 8)),
+        (0x146464, ("assets/background.png", Collision::None, 9)), // player spawn
+        (
+            0x143c97,
+            ("assets/vent_right.png", Collision::OneWay(Right), 10),
+        ),
+        (
+            0x143c98,
+            ("assets/vent_left.png", Collision::OneWay(Left), 11),
+        ),
+        (0x143c99, ("assets/vent_up.png", Collision::OneWay(Up), 12)),
+        (
+            0x143c9a,
+            ("assets/vent_down.png", Collision::OneWay(Down), 13),
+        ),
+        (0x14a0c8, ("assets/scrap_robot.png", Collision::None, 14)),
+        (0x14a0c9, ("assets/salvaged_robot.png", Collision::None, 15)),
+        (0x14a0ca, ("assets/checkpoint.png", Collision::None, 16)), // active checkpoint
+        (
+            0xe0e0e0,
+            ("assets/foreground_tile.png", Collision::None, 17),
+        ),
+        (0x00ff01, ("assets/bomb_cross.png", Collision::None, 18)),
+        (
+            0x00ff02,
+            ("assets/bomb_horizontal.png", Collision::None, 19),
+        ),
+        (0x00ff03, ("assets/bomb_vertical.png", Collision::None, 20)),
+        (0x00ff04, ("assets/bomb_fuse.png", Collision::None, 21)),
+        (0xc80000, ("assets/spikes.png", Collision::None, 22)),
+        (0xc80001, ("assets/laser_on.png", Collision::None, 23)),
+        (0xc80002, ("assets/laser_off.png", Collision::None, 24)),
+        (0xc80003, ("assets/crusher_down.png", Collision::Solid, 25)),
+        (0xc80004, ("assets/crusher_up.png", Collision::None, 26)),
+        (0xffc800, ("assets/pressure_plate.png", Collision::None, 27)),
+        (
+            0xffc801,
+            ("assets/pressure_plate_down.png", Collision::None, 28),
+        ),
+        (0x5a5a00, ("assets/fragile_floor.png", Collision::Solid, 29)),
+        (
+            0x5a5a01,
+            ("assets/fragile_floor_cracked.png", Collision::Solid, 30),
+        ),
+    ])
+}
+
+/// Loads every level in the order they are played.
+fn load_levels(
+    rl: &mut RaylibHandle,
+    thread: &RaylibThread,
+    tileset: &HashMap<u32, (&str, Collision, usize)>,
+) -> Vec<Level> {
+    vec![
+        Level::load_from_file(rl, thread, "assets/level1.png", tileset.clone()),
+        Level::load_from_file(rl, thread, "assets/level2.png", tileset.clone())
+            .with_inventory(Inventory::Fixed(Vec::new())),
+        Level::load_from_file(rl, thread, "assets/level3.png", tileset.clone()),
+        Level::load_from_file(rl, thread, "assets/level4.png", tileset.clone())
+            .with_inventory(Inventory::Fixed(Vec::new())),
+        Level::load_from_file(rl, thread, "assets/level5.png", tileset.clone())
+            .with_scale_range(0.5, 2.0)
+            .with_inventory(Inventory::Fixed(Vec::new()))
+            .with_music("assets/music_final.wav"),
+    ]
+}
+
+fn enter_next_level(game_state: &mut GameState, player: &mut Player, level: Level) {
+    game_state.current_level = level;
+    game_state.level_number += 1;
+    game_state.level_time = 0.0;
+    game_state.bombs_used = 0;
+    game_state.level_start_deaths = game_state.deaths;
+    game_state.level_start_bombs = game_state.current_level.starting_bombs(&game_state.bombs);
+    // also drops bombs still placed in the old level, their positions mean nothing here
+    restart_level(game_state, player);
 }
 
 fn render_title_screen(d: &mut RaylibDrawHandle) {
@@ -314,45 +587,142 @@ fn render_title_screen(d: &mut RaylibDrawHandle) {
     );
 }
 
-fn detonate_all_bombs(game_state: &mut GameState, explosions: &mut Vec<Explosion>) {
-    let mut bomb_positions: Vec<Vector2i> = Vec::new();
-    for (pos, tile) in game_state.current_level.tilemap.iter() {
-        if tile.id() == 8
-        /* if its a bomb */
-        {
-            if game_state.placed_bombs.contains(&pos) {
-                bomb_positions.push(pos.clone());
+/// Puts the current level and the player back to how they were when the level was entered.
+fn restart_level(game_state: &mut GameState, player: &mut Player) {
+    game_state.current_level.restart();
+    game_state.bombs = game_state.level_start_bombs.clone();
+    game_state.placed_bombs.clear();
+    game_state.detonations.clear();
+    game_state.crumbling.clear();
+    game_state.checkpoint = None;
+    player.velocity = Vector2::zero();
+    set_player_pos(game_state, player);
+}
+
+/// Swaps in the current level as it is on disk now. Bombs and checkpoints placed in it are
+/// gone, but the player stays where they are unless they'd be stuck in a wall.
+fn reload_level(
+    rl: &mut RaylibHandle,
+    thread: &RaylibThread,
+    tileset: &HashMap<u32, (&str, Collision, usize)>,
+    game_state: &mut GameState,
+    player: &mut Player,
+) {
+    game_state.current_level.reload(rl, thread, tileset.clone());
+    // bombs placed or picked up since entering the level come back with the map
+    game_state.bombs = game_state.level_start_bombs.clone();
+    game_state.placed_bombs.clear();
+    game_state.detonations.clear();
+    game_state.crumbling.clear();
+    game_state.checkpoint = None;
+    if game_state
+        .current_level
+        .tilemap
+        .collides(&player.hitbox(), player.scale(), Vector2::zero())
+    {
+        set_player_pos(game_state, player);
+    }
+}
+
+/// Carries out a developer console command, returns what to show in the console.
+fn run_command(
+    command: Command,
+    rl: &mut RaylibHandle,
+    thread: &RaylibThread,
+    tileset: &HashMap<u32, (&str, Collision, usize)>,
+    levels: &mut Vec<Level>,
+    game_state: &mut GameState,
+    player: &mut Player,
+) -> String {
+    match command {
+        Command::Level(number) => {
+            // levels are dropped once they're left, so start over from the files
+            let mut all = load_levels(rl, thread, tileset);
+            if number < 1 || number as usize > all.len() {
+                return format!("There are levels 1 to {}", all.len());
             }
+            *levels = all.split_off(number as usize);
+            game_state.level_number = number - 1;
+            game_state.transition = None;
+            game_state.won = false;
+            enter_next_level(game_state, player, all.pop().unwrap());
+            format!("Entered level {number}")
         }
-    }
-    game_state.placed_bombs.clear();
-    for bomb_pos in bomb_positions {
-        game_state
-            .current_level
-            .tilemap
-            .set_tile(bomb_pos.clone(), 0);
-        explosions.push(Explosion::new(bomb_pos.clone()));
-        for x in (bomb_pos.x - 1)..=(bomb_pos.x + 1) {
-            for y in (bomb_pos.y - 1)..=(bomb_pos.y + 1) {
-                if game_state
-                    .current_level
-                    .tilemap
-                    .get_tile(&Vector2i::new(x, y))
-                    .unwrap()
-                    .id()
-                    == 5
-                {
-                    game_state
-                        .current_level
-                        .tilemap
-                        .set_tile(Vector2i::new(x, y), 0);
-                }
+        Command::GiveBombs(count) => {
+            let count = count.min(MAX_BOMBS.saturating_sub(game_state.bombs.len()));
+            for _ in 0..count {
+                game_state.bombs.push(8);
             }
+            format!("Holding {} bombs", game_state.bombs.len())
+        }
+        Command::Noclip => {
+            player.noclip = !player.noclip;
+            format!("Noclip {}", if player.noclip { "on" } else { "off" })
+        }
+        Command::Scale(scale) => {
+            let level = &game_state.current_level;
+            if !(level.min_scale..=level.max_scale).contains(&scale) {
+                format!(
+                    "Scale must be between {} and {}",
+                    level.min_scale, level.max_scale
+                )
+            } else if player.set_scale(game_state, scale) {
+                format!("Scale set to {scale}")
+            } else {
+                format!("No room for scale {scale}")
+            }
+        }
+        Command::Tile(pos, id) => {
+            let tilemap = &mut game_state.current_level.tilemap;
+            if tilemap.tile(id).is_none() {
+                return format!("There is no tile {id}");
+            }
+            if tilemap.get_tile(&pos).is_none() {
+                return format!("{pos} is outside the level");
+            }
+            tilemap.set_tile(pos.clone(), id);
+            format!("Set {pos} to tile {id}")
+        }
+        Command::Flip(pos) => {
+            if !game_state.current_level.wires.contains_key(&pos) {
+                return format!("Nothing is wired to {pos}");
+            }
+            game_state.current_level.on_lever_flip(pos.x, pos.y);
+            format!("Flipped {pos}")
+        }
+        Command::Restart => {
+            restart_level(game_state, player);
+            "Restarted the level".to_string()
         }
     }
 }
 
+fn kill_player(game_state: &mut GameState, player: &mut Player) {
+    if player.is_dead() {
+        return;
+    }
+    player.kill();
+    game_state.events.push(GameEvent::PlayerDied);
+}
+
+/// Brings the player back at the last checkpoint, or restarts the level if there is none.
+fn respawn(game_state: &mut GameState, player: &mut Player) {
+    player.revive();
+    if game_state.checkpoint.is_some() {
+        set_player_pos(game_state, player);
+    } else {
+        restart_level(game_state, player);
+    }
+}
+
 fn set_player_pos(game_state: &GameState, player: &mut Player) {
+    if let Some(pos) = &game_state.checkpoint {
+        player.position = Vector2::new(
+            (pos.x * TILE_SIZE_PIXELS) as f32,
+            (pos.y * TILE_SIZE_PIXELS) as f32,
+        );
+        return;
+    }
     for (pos, tile) in game_state.current_level.tilemap.iter() {
         if tile.id() == 9 {
             player.position = Vector2::new(
@@ -0,0 +1,53 @@
+use crate::config;
+
+/// Tuning values for how the player runs and jumps, loaded from a `key = value` file so they
+/// can be changed without recompiling. Anything missing from the file keeps its default.
+#[derive(Clone)]
+pub struct MovementConfig {
+    pub run_acceleration: f32, // added to the horizontal velocity every tick a key is held
+    pub air_control: f32,      // fraction of the run acceleration available in the air
+    pub ground_drag: f32,      // velocity is multiplied by this every tick on the ground
+    pub air_drag: f32,         // and by this every tick in the air
+    pub gravity: f32,          // at scale 1
+    pub jump_velocity: f32,    // at scale 1.5
+    pub jump_cut: f32,         // upward velocity is multiplied by this when jump is released early
+    pub coyote_ticks: i32,     // how long after walking off a ledge a jump still works
+    pub jump_buffer_ticks: i32, // how long before landing a jump press is remembered
+}
+
+impl Default for MovementConfig {
+    fn default() -> Self {
+        Self {
+            run_acceleration: 1.5,
+            air_control: 1.0,
+            ground_drag: 0.75,
+            air_drag: 0.75,
+            gravity: 2.8,
+            jump_velocity: 90.0,
+            jump_cut: 0.5,
+            coyote_ticks: 10,
+            jump_buffer_ticks: 6,
+        }
+    }
+}
+
+impl MovementConfig {
+    pub fn load(path: &str) -> Self {
+        let values = config::load(path);
+        let mut movement = Self::default();
+        config::read(&values, "run_acceleration", &mut movement.run_acceleration);
+        config::read(&values, "air_control", &mut movement.air_control);
+        config::read(&values, "ground_drag", &mut movement.ground_drag);
+        config::read(&values, "air_drag", &mut movement.air_drag);
+        config::read(&values, "gravity", &mut movement.gravity);
+        config::read(&values, "jump_velocity", &mut movement.jump_velocity);
+        config::read(&values, "jump_cut", &mut movement.jump_cut);
+        config::read(&values, "coyote_ticks", &mut movement.coyote_ticks);
+        config::read(
+            &values,
+            "jump_buffer_ticks",
+            &mut movement.jump_buffer_ticks,
+        );
+        movement
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+
+use raylib::{
+    color::Color,
+    math::Vector2,
+    prelude::{RaylibDraw, RaylibDrawHandle},
+};
+
+use crate::{config, event::GameEvent, Vector2i, TILE_SIZE_PIXELS};
+
+/// How a burst of particles looks and moves. Every value can be overridden in the particle
+/// config with `<emitter>.<field> = value` lines, e.g. `debris.count = 12`.
+struct Emitter {
+    count: i32,
+    speed: f32,    // maximum initial speed in pixels per tick
+    lift: f32,     // extra upward speed added to every particle
+    gravity: f32,  // added to the vertical speed every tick
+    drag: f32,     // speed is multiplied by this every tick
+    lifetime: i32, // in ticks, particles fade out over it
+    size: f32,     // in pixels, particles shrink to nothing over their lifetime
+    color: Color,
+}
+
+impl Emitter {
+    fn load(values: &HashMap<String, String>, name: &str, defaults: Emitter) -> Self {
+        let mut emitter = defaults;
+        config::read(values, &format!("{name}.count"), &mut emitter.count);
+        config::read(values, &format!("{name}.speed"), &mut emitter.speed);
+        config::read(values, &format!("{name}.lift"), &mut emitter.lift);
+        config::read(values, &format!("{name}.gravity"), &mut emitter.gravity);
+        config::read(values, &format!("{name}.drag"), &mut emitter.drag);
+        // `render` divides by the lifetime
+        let mut lifetime = emitter.lifetime;
+        config::read(values, &format!("{name}.lifetime"), &mut lifetime);
+        if lifetime > 0 {
+            emitter.lifetime = lifetime;
+        } else {
+            println!("Invalid value for {name}.lifetime: {lifetime}");
+        }
+        config::read(values, &format!("{name}.size"), &mut emitter.size);
+        if let Some(hex) = values.get(&format!("{name}.color")) {
+            match u32::from_str_radix(hex, 16) {
+                Ok(rgba) => emitter.color = Color::get_color(rgba),
+                Err(_) => println!("Invalid value for {name}.color: {hex}"),
+            }
+        }
+        emitter
+    }
+}
+
+struct Particle {
+    position: Vector2,
+    velocity: Vector2,
+    age: i32,
+    emitter: usize, // index into `Particles::emitters`
+}
+
+/// Debris from destroyed walls, smoke from explosions and dust from landings.
+pub struct Particles {
+    particles: Vec<Particle>,
+    emitters: [Emitter; 3],
+}
+
+static DEBRIS: usize = 0;
+static SMOKE: usize = 1;
+static DUST: usize = 2;
+
+impl Particles {
+    pub fn load(path: &str) -> Self {
+        let values = config::load(path);
+        let debris = Emitter {
+            count: 10,
+            speed: 6.0,
+            lift: 4.0,
+            gravity: 0.6,
+            drag: 0.98,
+            lifetime: 45,
+            size: 8.0,
+            color: Color::new(90, 90, 90, 255),
+        };
+        let smoke = Emitter {
+            count: 16,
+            speed: 3.0,
+            lift: 1.0,
+            gravity: -0.05,
+            drag: 0.92,
+            lifetime: 60,
+            size: 24.0,
+            color: Color::new(60, 60, 60, 160),
+        };
+        let dust = Emitter {
+            count: 6,
+            speed: 2.0,
+            lift: 0.5,
+            gravity: 0.0,
+            drag: 0.9,
+            lifetime: 20,
+            size: 8.0,
+            color: Color::new(200, 200, 200, 180),
+        };
+        Self {
+            particles: Vec::new(),
+            emitters: [
+                Emitter::load(&values, "debris", debris),
+                Emitter::load(&values, "smoke", smoke),
+                Emitter::load(&values, "dust", dust),
+            ],
+        }
+    }
+
+    pub fn handle_events(&mut self, d: &RaylibDrawHandle, events: &[GameEvent]) {
+        for event in events {
+            match event {
+                GameEvent::TileDestroyed { position } => {
+                    self.emit(d, DEBRIS, tile_center(position));
+                }
+                GameEvent::BombExploded { position } => {
+                    self.emit(d, SMOKE, tile_center(position));
+                }
+                GameEvent::PlayerLanded { feet, .. } => self.emit(d, DUST, *feet),
+                _ => {}
+            }
+        }
+    }
+
+    fn emit(&mut self, d: &RaylibDrawHandle, emitter: usize, position: Vector2) {
+        let config = &self.emitters[emitter];
+        for _ in 0..config.count {
+            let angle = d.get_random_value::<i32>(0..360) as f32 * std::f32::consts::PI / 180.0;
+            let speed = config.speed * d.get_random_value::<i32>(20..100) as f32 / 100.0;
+            self.particles.push(Particle {
+                position,
+                velocity: Vector2::new(angle.cos() * speed, angle.sin() * speed - config.lift),
+                age: 0,
+                emitter,
+            });
+        }
+    }
+
+    pub fn update(&mut self) {
+        let emitters = &self.emitters;
+        self.particles.retain_mut(|particle| {
+            let config = &emitters[particle.emitter];
+            particle.age += 1;
+            particle.velocity.y += config.gravity;
+            particle.velocity *= config.drag;
+            particle.position += particle.velocity;
+            particle.age < config.lifetime
+        });
+    }
+
+    /// Particles are plain untextured rectangles, so raylib batches all of them into as
+    /// few draw calls as possible.
+    pub fn render(&self, d: &mut impl RaylibDraw) {
+        for particle in self.particles.iter() {
+            let config = &self.emitters[particle.emitter];
+            let life = 1.0 - particle.age as f32 / config.lifetime as f32;
+            let size = config.size * life;
+            d.draw_rectangle_v(
+                particle.position - Vector2::new(size / 2.0, size / 2.0),
+                Vector2::new(size, size),
+                config.color.alpha(life * config.color.a as f32 / 255.0),
+            );
+        }
+    }
+}
+
+fn tile_center(pos: &Vector2i) -> Vector2 {
+    Vector2::new(
+        (pos.x as f32 + 0.5) * TILE_SIZE_PIXELS as f32,
+        (pos.y as f32 + 0.5) * TILE_SIZE_PIXELS as f32,
+    )
+}
@@ -5,31 +5,74 @@ use raylib::{
     texture::Texture2D,
 };
 
-use crate::{utils::nearest_lower_multiple, GameState, Vector2i, SCALE, TILE_SIZE_PIXELS};
+use crate::{
+    animation::Animator, event::GameEvent, movement::MovementConfig, utils::nearest_lower_multiple,
+    GameState, Vector2i, SCALE, TILE_SIZE_PIXELS,
+};
+
+static DEATH_TICKS: i32 = 60; // length of the death animation
+static SNAP_STEP: f32 = 0.5; // distance between sizes when snap scaling is on
+static FLY_SPEED: f32 = 8.0; // pixels per tick in noclip
 
 pub struct Player {
     pub position: Vector2, // position in pixels on the screen
-    texture: Texture2D,
+    animator: Animator,
+    dead_texture: Texture2D,
+    death_timer: Option<i32>,
     scale: f32,
+    pub snap_scaling: bool, // jump between fixed sizes instead of scaling freely
+    target_scale: Option<f32>, // snap size the player is growing or shrinking towards
     pub velocity: Vector2,
     pub on_ground: bool,
-    tick_since_last_ground: i32,
+    pub noclip: bool, // flies through everything, set from the console
+    pub tick_since_last_ground: i32,
+    jump_buffer: i32, // ticks left in which a jump press still counts
+    jumping: bool,    // rising from a jump, not e.g. thrown up by an explosion
+    movement: MovementConfig,
 }
 
 impl Player {
-    pub fn new(position: Vector2, texture: Texture2D) -> Self {
+    pub fn new(position: Vector2, sheet: Texture2D, dead_texture: Texture2D) -> Self {
         Self {
             position,
             scale: 1.0,
-            texture,
+            animator: Animator::new(sheet),
+            dead_texture,
+            death_timer: None,
+            snap_scaling: false,
+            target_scale: None,
             on_ground: false,
+            noclip: false,
             velocity: Vector2::zero(),
             tick_since_last_ground: 0,
+            jump_buffer: 0,
+            jumping: false,
+            movement: MovementConfig::default(),
         }
     }
 
-    pub fn render(&mut self, display: &mut RaylibDrawHandle, game_state: &mut GameState) {
-        self.velocity.y += 2.8 * ((self.scale + 1.0) / 2.0); // Gravity
+    pub fn with_movement(mut self, movement: MovementConfig) -> Self {
+        self.movement = movement;
+        self
+    }
+
+    pub fn set_movement(&mut self, movement: MovementConfig) {
+        self.movement = movement;
+    }
+
+    /// Moves the player for this tick, drawing happens separately in `draw`.
+    pub fn update(&mut self, display: &mut RaylibDrawHandle, game_state: &mut GameState) {
+        if let Some(timer) = self.death_timer {
+            self.death_timer = Some(timer + 1);
+            return;
+        }
+        if self.noclip {
+            self.fly(display);
+            self.clamp_position(display);
+            return;
+        }
+
+        self.velocity.y += self.movement.gravity * ((self.scale + 1.0) / 2.0); // Gravity
 
         if self.position.y
             >= display.get_screen_height() as f32 - TILE_SIZE_PIXELS as f32 * self.scale
@@ -37,32 +80,110 @@ impl Player {
             self.on_ground = true;
         }
 
-        self.handle_input(display);
+        self.handle_input(display, game_state);
 
+        let was_on_ground = self.on_ground;
         self.move_vertical(game_state);
+        if self.on_ground && !was_on_ground {
+            let hitbox = self.hitbox();
+            game_state.events.push(GameEvent::PlayerLanded {
+                feet: Vector2::new(hitbox.x + hitbox.width / 2.0, hitbox.y + hitbox.height),
+                scale: self.scale,
+            });
+        }
         self.move_horizontal(game_state);
+        let scale = self.scale;
         self.handle_scaling(game_state, display);
+        if self.scale > scale {
+            game_state.events.push(GameEvent::Grew);
+        } else if self.scale < scale {
+            game_state.events.push(GameEvent::Shrank);
+        }
+        self.animator
+            .update(self.velocity, self.on_ground, self.scale != scale);
 
-        self.velocity *= 0.75; // apply drag
+        // apply drag
+        self.velocity *= if self.on_ground {
+            self.movement.ground_drag
+        } else {
+            self.movement.air_drag
+        };
 
         self.tick_since_last_ground += 1;
 
         self.clamp_position(display); // don't fall out of the screen
+    }
+
+    pub fn draw(&self, display: &mut impl RaylibDraw) {
+        match self.death_timer {
+            Some(timer) => self.render_death(display, timer),
+            None => self.animator.render(display, self.position, self.scale),
+        }
+    }
+
+    fn render_death(&self, display: &mut impl RaylibDraw, timer: i32) {
+        let progress = timer as f32 / DEATH_TICKS as f32;
+        let shake = if timer < DEATH_TICKS / 3 {
+            ((timer % 4) - 2) as f32 * SCALE as f32
+        } else {
+            0.0
+        };
         display.draw_texture_ex(
-            &self.texture,
-            Vector2::new(self.position.x as f32, self.position.y as f32),
+            &self.dead_texture,
+            Vector2::new(self.position.x + shake, self.position.y),
             0.0,
             self.scale * SCALE as f32,
-            Color::WHITE,
+            Color::WHITE.alpha(1.0 - progress.min(1.0)),
         );
     }
 
+    pub fn kill(&mut self) {
+        if self.death_timer.is_none() {
+            self.death_timer = Some(0);
+        }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.death_timer.is_some()
+    }
+
+    /// Whether the death animation has played out and the player can respawn.
+    pub fn death_finished(&self) -> bool {
+        self.death_timer.is_some_and(|timer| timer >= DEATH_TICKS)
+    }
+
+    pub fn revive(&mut self) {
+        self.death_timer = None;
+        self.scale = 1.0;
+        self.target_scale = None;
+        self.velocity = Vector2::zero();
+        self.jump_buffer = 0;
+        self.jumping = false;
+        self.animator.reset();
+    }
+
+    /// Moves with W A S D ignoring gravity and collisions.
+    fn fly(&mut self, display: &RaylibDrawHandle) {
+        let keys = [
+            (raylib::ffi::KeyboardKey::KEY_W, Vector2::new(0.0, -1.0)),
+            (raylib::ffi::KeyboardKey::KEY_A, Vector2::new(-1.0, 0.0)),
+            (raylib::ffi::KeyboardKey::KEY_S, Vector2::new(0.0, 1.0)),
+            (raylib::ffi::KeyboardKey::KEY_D, Vector2::new(1.0, 0.0)),
+        ];
+        self.velocity = Vector2::zero();
+        for (key, direction) in keys {
+            if display.is_key_down(key) {
+                self.position += direction * FLY_SPEED;
+            }
+        }
+    }
+
     fn move_horizontal(&mut self, game_state: &mut GameState) {
         self.position.x += self.velocity.x;
-        if game_state.current_level.tilemap.collides(&self.hitbox()) {
+        if self.collides(game_state, Vector2::new(self.velocity.x, 0.0)) {
             self.position.x -= self.velocity.x;
             let mut i = 0;
-            while !game_state.current_level.tilemap.collides(&self.hitbox()) {
+            while !self.collides(game_state, Vector2::new(self.velocity.x, 0.0)) {
                 self.velocity.x /= 1.5;
                 self.position.x += self.velocity.x;
                 if i >= 10 {
@@ -77,15 +198,16 @@ impl Player {
     fn move_vertical(&mut self, game_state: &mut GameState) {
         self.on_ground = false;
         self.position.y += self.velocity.y;
-        if game_state.current_level.tilemap.collides(&self.hitbox()) {
+        if self.collides(game_state, Vector2::new(0.0, self.velocity.y)) {
             self.position.y -= self.velocity.y;
             self.on_ground = self.velocity.y >= 0.0;
             if self.on_ground {
                 self.tick_since_last_ground = 0;
+                self.jumping = false;
             }
             let mut i = 0;
 
-            while !game_state.current_level.tilemap.collides(&self.hitbox()) {
+            while !self.collides(game_state, Vector2::new(0.0, self.velocity.y)) {
                 self.velocity.y /= 1.5;
                 self.position.y += self.velocity.y;
                 if i >= 10 {
@@ -97,25 +219,56 @@ impl Player {
             if self.on_ground {
                 self.velocity.y = 0.0;
             }
-        } else {
-            self.on_ground = self.tick_since_last_ground <= 10;
         }
     }
 
-    fn handle_input(&mut self, display: &mut RaylibDrawHandle) {
-        let speed = 1.5;
+    fn handle_input(&mut self, display: &mut RaylibDrawHandle, game_state: &mut GameState) {
+        let mut speed = self.movement.run_acceleration;
+        if !self.on_ground {
+            speed *= self.movement.air_control;
+        }
         if display.is_key_down(raylib::ffi::KeyboardKey::KEY_D) {
             self.velocity.x += speed;
         }
         if display.is_key_down(raylib::ffi::KeyboardKey::KEY_A) {
             self.velocity.x -= speed;
         }
-        if self.on_ground && display.is_key_down(raylib::ffi::KeyboardKey::KEY_SPACE) {
-            self.velocity.y = -90.0 * (self.scale / 1.5); // jump
-            self.tick_since_last_ground = 100;
+
+        if display.is_key_pressed(raylib::ffi::KeyboardKey::KEY_SPACE) {
+            self.jump_buffer = self.movement.jump_buffer_ticks;
+        }
+        // coyote time: still allow the jump shortly after walking off a ledge
+        let can_jump = self.on_ground || self.tick_since_last_ground <= self.movement.coyote_ticks;
+        if self.jump_buffer > 0 && can_jump {
+            self.velocity.y = -self.movement.jump_velocity * (self.scale / 1.5); // jump
+            self.tick_since_last_ground = self.movement.coyote_ticks + 1;
+            self.jump_buffer = 0;
+            self.jumping = true;
+            game_state.events.push(GameEvent::Jumped);
+        }
+        self.jump_buffer = (self.jump_buffer - 1).max(0);
+
+        if display.is_key_released(raylib::ffi::KeyboardKey::KEY_SPACE)
+            && self.jumping
+            && self.velocity.y < 0.0
+        {
+            self.velocity.y *= self.movement.jump_cut; // short hop
+            self.jumping = false;
         }
     }
 
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    pub fn center(&self) -> Vector2 {
+        let hitbox = self.hitbox();
+        Vector2::new(
+            hitbox.x + hitbox.width / 2.0,
+            hitbox.y + hitbox.height / 2.0,
+        )
+    }
+
     pub fn hitbox(&self) -> Rectangle {
         Rectangle {
             x: self.position.x + TILE_SIZE_PIXELS as f32 * 0.125,
@@ -138,19 +291,77 @@ impl Player {
 
     fn handle_scaling(&mut self, game_state: &mut GameState, display: &mut RaylibDrawHandle) {
         let size_change = 0.05;
-        if self.scale < 2.0 && display.is_key_down(raylib::ffi::KeyboardKey::KEY_UP) {
-            self.scale += size_change * 2.0;
-            self.position.y -= size_change * 2.0 * TILE_SIZE_PIXELS as f32;
-            if game_state.current_level.tilemap.collides(&self.hitbox()) {
-                self.scale -= size_change;
-                self.position.y += size_change * TILE_SIZE_PIXELS as f32;
+        let min_scale = game_state.current_level.min_scale;
+        let max_scale = game_state.current_level.max_scale;
+
+        if self.snap_scaling {
+            if display.is_key_pressed(raylib::ffi::KeyboardKey::KEY_UP) {
+                let next = ((self.scale + 0.01) / SNAP_STEP).ceil() * SNAP_STEP;
+                self.target_scale = Some(next.min(max_scale));
+            }
+            if display.is_key_pressed(raylib::ffi::KeyboardKey::KEY_DOWN) {
+                let next = ((self.scale - 0.01) / SNAP_STEP).floor() * SNAP_STEP;
+                self.target_scale = Some(next.max(min_scale));
             }
-            self.scale -= size_change;
-            self.position.y += size_change * TILE_SIZE_PIXELS as f32;
+            if let Some(target) = self.target_scale {
+                let scale = self.scale;
+                if target > scale {
+                    self.resize(game_state, (scale + size_change).min(target));
+                } else {
+                    self.resize(game_state, (scale - size_change).max(target));
+                }
+                // stop once the size is reached or there's no room to get there
+                if self.scale == target || self.scale == scale {
+                    self.target_scale = None;
+                }
+            }
+            return;
+        }
+
+        if self.scale < max_scale && display.is_key_down(raylib::ffi::KeyboardKey::KEY_UP) {
+            self.resize(game_state, (self.scale + size_change).min(max_scale));
         }
-        if self.scale > 1.0 && display.is_key_down(raylib::ffi::KeyboardKey::KEY_DOWN) {
-            self.scale -= size_change;
+        if self.scale > min_scale && display.is_key_down(raylib::ffi::KeyboardKey::KEY_DOWN) {
+            // clamp so that the player can get back to exactly the minimum and fit through vents
+            self.resize(game_state, (self.scale - size_change).max(min_scale));
+        }
+    }
+
+    /// Resizes straight to `scale`, returns false if there is no room for it.
+    pub fn set_scale(&mut self, game_state: &GameState, scale: f32) -> bool {
+        self.target_scale = None;
+        self.resize(game_state, scale);
+        self.scale == scale
+    }
+
+    /// Changes the scale while keeping the feet in place. Grows from the center if there
+    /// is room, otherwise from the left or right edge, and keeps the old size if the new
+    /// one doesn't fit anywhere, so the player never ends up inside a wall.
+    fn resize(&mut self, game_state: &GameState, scale: f32) {
+        let old_scale = self.scale;
+        let old_position = self.position;
+        let old_hitbox = self.hitbox();
+
+        self.scale = scale;
+        let new_hitbox = self.hitbox();
+        self.position.y += (old_hitbox.y + old_hitbox.height) - (new_hitbox.y + new_hitbox.height);
+        let growth = new_hitbox.width - old_hitbox.width;
+        for shift in [growth / 2.0, 0.0, growth] {
+            self.position.x = old_position.x - shift;
+            if !self.collides(game_state, Vector2::zero()) {
+                return;
+            }
         }
+
+        self.scale = old_scale;
+        self.position = old_position;
+    }
+
+    fn collides(&self, game_state: &GameState, motion: Vector2) -> bool {
+        game_state
+            .current_level
+            .tilemap
+            .collides(&self.hitbox(), self.scale, motion)
     }
 
     pub fn tile_from_center(&self) -> Vector2i {
@@ -0,0 +1,125 @@
+use raylib::{
+    color::Color,
+    consts::KeyboardKey,
+    prelude::{RaylibDraw, RaylibDrawHandle},
+};
+
+use crate::config;
+
+static SETTINGS_PATH: &str = "settings.cfg";
+static VOLUME_STEP: f32 = 0.1;
+static DISPLAY_TICKS: i32 = 90; // how long the readout stays up after a change
+
+/// Player preferences that survive restarts of the game.
+pub struct Settings {
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    pub screen_effects: bool, // shake, flashes and zoom, off for players sensitive to them
+    display: i32,             // ticks left on the readout, not saved
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            music_volume: 0.8,
+            sfx_volume: 1.0,
+            screen_effects: true,
+            display: 0,
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> Self {
+        let values = config::load(SETTINGS_PATH);
+        let mut settings = Self::default();
+        config::read(&values, "master_volume", &mut settings.master_volume);
+        config::read(&values, "music_volume", &mut settings.music_volume);
+        config::read(&values, "sfx_volume", &mut settings.sfx_volume);
+        config::read(&values, "screen_effects", &mut settings.screen_effects);
+        // the file may have been edited by hand
+        for volume in [
+            &mut settings.master_volume,
+            &mut settings.music_volume,
+            &mut settings.sfx_volume,
+        ] {
+            *volume = volume.clamp(0.0, 1.0);
+        }
+        settings
+    }
+
+    pub fn save(&self) {
+        config::save(
+            SETTINGS_PATH,
+            &[
+                ("master_volume", &self.master_volume),
+                ("music_volume", &self.music_volume),
+                ("sfx_volume", &self.sfx_volume),
+                ("screen_effects", &self.screen_effects),
+            ],
+        );
+    }
+
+    /// 1/2 turn the master volume down/up, 3/4 the music and 5/6 the sound effects,
+    /// 7 toggles screen effects. Saves and returns true if anything changed.
+    pub fn handle_keys(&mut self, d: &RaylibDrawHandle) -> bool {
+        self.display = (self.display - 1).max(0);
+
+        let buses = [
+            (KeyboardKey::KEY_ONE, KeyboardKey::KEY_TWO),
+            (KeyboardKey::KEY_THREE, KeyboardKey::KEY_FOUR),
+            (KeyboardKey::KEY_FIVE, KeyboardKey::KEY_SIX),
+        ];
+        let mut changed = false;
+        for (i, (down, up)) in buses.into_iter().enumerate() {
+            let step = if d.is_key_pressed(down) {
+                -VOLUME_STEP
+            } else if d.is_key_pressed(up) {
+                VOLUME_STEP
+            } else {
+                continue;
+            };
+            let volume = match i {
+                0 => &mut self.master_volume,
+                1 => &mut self.music_volume,
+                _ => &mut self.sfx_volume,
+            };
+            *volume = ((*volume + step) * 10.0).round().clamp(0.0, 10.0) / 10.0;
+            changed = true;
+        }
+        if d.is_key_pressed(KeyboardKey::KEY_SEVEN) {
+            self.screen_effects = !self.screen_effects;
+            changed = true;
+        }
+
+        if changed {
+            self.save();
+            self.display = DISPLAY_TICKS;
+        }
+        changed
+    }
+
+    /// Shows the current settings for a moment after one of them was changed.
+    pub fn render(&self, d: &mut RaylibDrawHandle) {
+        if self.display == 0 {
+            return;
+        }
+        let text = format!(
+            "MASTER {}%   MUSIC {}%   SFX {}%   EFFECTS {}",
+            (self.master_volume * 100.0).round(),
+            (self.music_volume * 100.0).round(),
+            (self.sfx_volume * 100.0).round(),
+            if self.screen_effects { "ON" } else { "OFF" }
+        );
+        let width = d.measure_text(&text, 20);
+        d.draw_text(
+            &text,
+            d.get_screen_width() / 2 - width / 2,
+            d.get_screen_height() - 36,
+            20,
+            Color::WHITE.alpha(self.display as f32 / DISPLAY_TICKS as f32),
+        );
+    }
+}
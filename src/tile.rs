@@ -1,8 +1,51 @@
-use raylib::{texture::Texture2D, RaylibHandle, RaylibThread};
+use raylib::{math::Vector2, texture::Texture2D, RaylibHandle, RaylibThread};
+
+static VENT_MAX_SCALE: f32 = 1.0; // vents only fit a player at minimum size
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Direction {
+    /// Whether moving by `motion` goes against this direction.
+    fn opposes(&self, motion: Vector2) -> bool {
+        match self {
+            Direction::Left => motion.x > 0.0,
+            Direction::Right => motion.x < 0.0,
+            Direction::Up => motion.y > 0.0,
+            Direction::Down => motion.y < 0.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Collision {
+    None,
+    Solid,
+    /// Passable only while the player is at its minimum size.
+    Vent,
+    /// A vent that can only be crawled through in one direction.
+    OneWay(Direction),
+}
+
+impl Collision {
+    pub fn blocks(&self, scale: f32, motion: Vector2) -> bool {
+        match self {
+            Collision::None => false,
+            Collision::Solid => true,
+            Collision::Vent => scale > VENT_MAX_SCALE,
+            Collision::OneWay(direction) => scale > VENT_MAX_SCALE || direction.opposes(motion),
+        }
+    }
+}
 
 pub struct Tile {
     texture: Texture2D,
-    solid: bool,
+    collision: Collision,
     id: usize,
 }
 
@@ -11,12 +54,12 @@ impl Tile {
         rl: &mut RaylibHandle,
         thread: &RaylibThread,
         path: &str,
-        solid: bool,
+        collision: Collision,
         id: usize,
     ) -> Self {
         Self {
             texture: rl.load_texture(thread, path).unwrap(),
-            solid,
+            collision,
             id,
         }
     }
@@ -25,8 +68,8 @@ impl Tile {
         &self.texture
     }
 
-    pub fn solid(&self) -> bool {
-        self.solid
+    pub fn collision(&self) -> Collision {
+        self.collision
     }
 
     pub fn id(&self) -> usize {
@@ -1,15 +1,68 @@
 use std::collections::HashMap;
 
+use image::{Rgba, RgbaImage};
 use raylib::{
     color::Color,
     math::{Rectangle, Vector2},
-    prelude::{RaylibDraw, RaylibDrawHandle},
+    prelude::RaylibDraw,
 };
 
-use crate::{tile::Tile, Vector2i, SCALE, TILE_SIZE_PIXELS};
+use crate::{
+    tile::{Collision, Tile},
+    Vector2i, TILEMAP_HEIGHT, TILEMAP_WIDTH, TILE_SIZE_PIXELS,
+};
+
+/// Reads the tile ids out of a color coded level image. Pixels that aren't in the tileset
+/// become background tiles, except transparent ones in decoration layers, which stay empty.
+pub fn decode_tiles(
+    image: &RgbaImage,
+    tileset: &HashMap<u32, (&str, Collision, usize)>,
+    decoration: bool,
+) -> HashMap<Vector2i, usize> {
+    let mut tiles = HashMap::new();
+    for (x, y, pixel) in image.enumerate_pixels() {
+        let [r, g, b, a] = pixel.0;
+        let code = (r as u32) << 16 | (g as u32) << 8 | b as u32;
+        if decoration && a == 0 {
+            continue;
+        }
+        let id = match tileset.get(&code) {
+            Some(entry) if a == 255 => entry.2,
+            _ => {
+                println!("Unknown color code: {code}");
+                0
+            }
+        };
+        tiles.insert(Vector2i::new(x as i32, y as i32), id);
+    }
+    tiles
+}
+
+/// The inverse of `decode_tiles`: a map sized image with every tile in its tileset color.
+/// Positions without a tile stay transparent, as in decoration layers.
+pub fn encode_tiles(
+    tiles: &HashMap<Vector2i, usize>,
+    tileset: &HashMap<u32, (&str, Collision, usize)>,
+) -> RgbaImage {
+    let mut image = RgbaImage::new(TILEMAP_WIDTH as u32, TILEMAP_HEIGHT as u32);
+    for (pos, id) in tiles.iter() {
+        let Some((code, _)) = tileset.iter().find(|(_code, entry)| entry.2 == *id) else {
+            println!("Tile {id} at {pos} is not in the tileset");
+            continue;
+        };
+        let Some(pixel) = image.get_pixel_mut_checked(pos.x as u32, pos.y as u32) else {
+            println!("Tile {id} at {pos} is outside the map");
+            continue;
+        };
+        let [_, r, g, b] = code.to_be_bytes();
+        *pixel = Rgba([r, g, b, 255]);
+    }
+    image
+}
 
 pub struct Tilemap {
     tiles: HashMap<Vector2i, usize>,
+    initial_tiles: HashMap<Vector2i, usize>,
     tileset: Vec<Tile>,
 }
 
@@ -17,10 +70,20 @@ impl Tilemap {
     pub fn new(tileset: Vec<Tile>) -> Self {
         Self {
             tiles: HashMap::new(),
+            initial_tiles: HashMap::new(),
             tileset,
         }
     }
 
+    /// Remembers the current tiles as the state `reset` goes back to.
+    pub fn save_initial_state(&mut self) {
+        self.initial_tiles = self.tiles.clone();
+    }
+
+    pub fn reset(&mut self) {
+        self.tiles = self.initial_tiles.clone();
+    }
+
     pub fn set_tile(&mut self, pos: Vector2i, tile: usize) {
         self.tiles.insert(pos, tile);
     }
@@ -33,26 +96,57 @@ impl Tilemap {
         self.tileset.get(*tile_id.unwrap())
     }
 
-    pub fn render(&self, d: &mut RaylibDrawHandle) {
-        for (pos, _id) in self.tiles.iter() {
+    /// Looks up a tile of the tileset by its id.
+    pub fn tile(&self, id: usize) -> Option<&Tile> {
+        self.tileset.get(id)
+    }
+
+    /// Turns the tilemap back into the color coded image it was loaded from, see
+    /// `encode_tiles`.
+    pub fn to_image(&self, tileset: &HashMap<u32, (&str, Collision, usize)>) -> RgbaImage {
+        encode_tiles(&self.tiles, tileset)
+    }
+
+    pub fn save(&self, path: &str, tileset: &HashMap<u32, (&str, Collision, usize)>) {
+        if let Err(err) = self.to_image(tileset).save(path) {
+            println!("Could not save {path}: {err}");
+        }
+    }
+
+    pub fn render(&self, d: &mut impl RaylibDraw) {
+        self.render_over(d, None);
+    }
+
+    /// Draws the tiles on top of a background decoration layer. Empty tiles are left out
+    /// where the decoration has something to show, everything else covers it.
+    pub fn render_over(&self, d: &mut impl RaylibDraw, background: Option<&Tilemap>) {
+        for (pos, id) in self.tiles.iter() {
+            if matches!(id, 0 | 9)
+                && background.is_some_and(|background| background.tiles.contains_key(pos))
+            {
+                continue;
+            }
+            let texture = self
+                .get_tile(&pos)
+                .unwrap_or(self.tileset.get(0).unwrap())
+                .texture();
             d.draw_texture_ex(
-                &self
-                    .get_tile(&pos)
-                    .unwrap_or(self.tileset.get(0).unwrap())
-                    .texture(),
+                texture,
                 Vector2::new(pos.x as f32, pos.y as f32).scale_by((TILE_SIZE_PIXELS) as f32),
                 0.0,
-                SCALE as f32,
+                TILE_SIZE_PIXELS as f32 / texture.width as f32, // stretch smaller textures to fill the tile
                 Color::WHITE,
             )
         }
     }
 
-    pub fn collides(&self, hitbox: &Rectangle) -> bool {
+    /// Checks whether `hitbox` overlaps any tile that blocks a player of the given `scale`
+    /// moving by `motion`. Pass a zero `motion` for checks that aren't caused by movement.
+    pub fn collides(&self, hitbox: &Rectangle, scale: f32, motion: Vector2) -> bool {
         for tile in self.tiles.keys() {
             {
                 let tile = self.get_tile(tile);
-                if tile.is_some() && !tile.unwrap().solid() {
+                if tile.is_some() && !tile.unwrap().collision().blocks(scale, motion) {
                     continue;
                 }
             }
@@ -97,4 +191,65 @@ impl Tilemap {
             tileset: &self.tileset,
         }
     }
+
+    /// Iterates over the tiles that overlap `hitbox`.
+    pub fn overlapping<'a>(
+        &'a self,
+        hitbox: &'a Rectangle,
+    ) -> impl Iterator<Item = (&'a Vector2i, &'a Tile)> {
+        self.iter().filter(|(pos, _tile)| {
+            Rectangle {
+                x: (pos.x * TILE_SIZE_PIXELS) as f32,
+                y: (pos.y * TILE_SIZE_PIXELS) as f32,
+                width: (TILE_SIZE_PIXELS) as f32,
+                height: (TILE_SIZE_PIXELS) as f32,
+            }
+            .check_collision_recs(hitbox)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tileset;
+
+    /// Decodes the image at `path`, encodes the tiles again and checks nothing changed.
+    fn round_trip(path: &str, decoration: bool) {
+        let tileset = tileset();
+        let image = image::open(path).unwrap().to_rgba8();
+        let tiles = decode_tiles(&image, &tileset, decoration);
+        let encoded = encode_tiles(&tiles, &tileset);
+        assert_eq!(
+            encoded.dimensions(),
+            (TILEMAP_WIDTH as u32, TILEMAP_HEIGHT as u32)
+        );
+        assert!(
+            decode_tiles(&encoded, &tileset, decoration) == tiles,
+            "{path} changed on the round trip"
+        );
+    }
+
+    #[test]
+    fn levels_round_trip() {
+        for number in 1..=5 {
+            round_trip(&format!("assets/level{number}.png"), false);
+        }
+    }
+
+    #[test]
+    fn decoration_layers_round_trip() {
+        round_trip("assets/level1_bg.png", true);
+        round_trip("assets/level1_fg.png", true);
+    }
+
+    #[test]
+    fn empty_layers_keep_the_map_size() {
+        let image = encode_tiles(&HashMap::new(), &tileset());
+        assert_eq!(
+            image.dimensions(),
+            (TILEMAP_WIDTH as u32, TILEMAP_HEIGHT as u32)
+        );
+        assert!(decode_tiles(&image, &tileset(), true).is_empty());
+    }
 }
@@ -0,0 +1,126 @@
+use raylib::{
+    color::Color,
+    consts::KeyboardKey,
+    prelude::{RaylibDraw, RaylibDrawHandle},
+};
+
+static WIPE_TICKS: i32 = 30; // length of the wipe out of a finished level
+static FADE_TICKS: i32 = 30; // length of the fade into the next one
+
+/// How the player did on the level they just finished.
+pub struct LevelStats {
+    pub level_number: i32,
+    pub time: f32,
+    pub bombs_used: i32,
+    pub deaths: i32,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Phase {
+    WipeOut,
+    Card,
+    FadeIn,
+}
+
+/// Wipes the finished level away, shows its completion card until the player continues,
+/// then fades the next level in.
+pub struct Transition {
+    phase: Phase,
+    tick: i32,
+    stats: LevelStats,
+}
+
+impl Transition {
+    pub fn new(stats: LevelStats) -> Self {
+        Self {
+            phase: Phase::WipeOut,
+            tick: 0,
+            stats,
+        }
+    }
+
+    /// Advances the transition, returns true on the tick the next level should be loaded.
+    pub fn update(&mut self, d: &RaylibDrawHandle) -> bool {
+        self.tick += 1;
+        match self.phase {
+            Phase::WipeOut => {
+                if self.tick >= WIPE_TICKS {
+                    self.phase = Phase::Card;
+                    self.tick = 0;
+                }
+            }
+            Phase::Card => {
+                if d.is_key_pressed(KeyboardKey::KEY_ENTER) {
+                    self.phase = Phase::FadeIn;
+                    self.tick = 0;
+                    return true;
+                }
+            }
+            Phase::FadeIn => {}
+        }
+        false
+    }
+
+    /// Whether the level is still covered, i.e. gameplay should stay paused.
+    pub fn blocks_gameplay(&self) -> bool {
+        self.phase != Phase::FadeIn
+    }
+
+    pub fn finished(&self) -> bool {
+        self.phase == Phase::FadeIn && self.tick >= FADE_TICKS
+    }
+
+    /// Draws the transition on top of whatever level is currently shown.
+    pub fn render(&self, d: &mut RaylibDrawHandle) {
+        let width = d.get_screen_width();
+        let height = d.get_screen_height();
+        match self.phase {
+            Phase::WipeOut => {
+                let progress = self.tick as f32 / WIPE_TICKS as f32;
+                d.draw_rectangle(0, 0, (width as f32 * progress) as i32, height, Color::BLACK);
+            }
+            Phase::Card => {
+                d.draw_rectangle(0, 0, width, height, Color::BLACK);
+                self.render_card(d);
+            }
+            Phase::FadeIn => {
+                let progress = self.tick as f32 / FADE_TICKS as f32;
+                d.draw_rectangle(0, 0, width, height, Color::BLACK.alpha(1.0 - progress));
+            }
+        }
+    }
+
+    fn render_card(&self, d: &mut RaylibDrawHandle) {
+        let seconds = self.stats.time as i32;
+        let lines = [
+            format!("TIME: {:02}:{:02}", seconds / 60, seconds % 60),
+            format!("BOMBS USED: {}", self.stats.bombs_used),
+            format!("DEATHS: {}", self.stats.deaths),
+        ];
+
+        let center = d.get_screen_width() / 2;
+        let top = d.get_screen_height() / 2 - 150;
+        let text = format!("LEVEL {} COMPLETE", self.stats.level_number);
+        let width = d.measure_text(&text, 64);
+        d.draw_text(&text, center - width / 2, top, 64, Color::WHITE);
+        for (i, line) in lines.iter().enumerate() {
+            let width = d.measure_text(line, 32);
+            d.draw_text(
+                line,
+                center - width / 2,
+                top + 100 + i as i32 * 40,
+                32,
+                Color::WHITE,
+            );
+        }
+        let text = "PRESS <ENTER> TO CONTINUE";
+        let width = d.measure_text(text, 32);
+        d.draw_text(
+            text,
+            center - width / 2,
+            top + 260 - (8.0 * d.get_time().sin()) as i32,
+            32,
+            Color::WHITE,
+        );
+    }
+}
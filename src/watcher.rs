@@ -0,0 +1,65 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::mpsc::{channel, Receiver},
+};
+
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+
+/// Notices when files in a directory are changed, added or removed while the game runs,
+/// through the OS file events (inotify on Linux).
+pub struct AssetWatcher {
+    dir: PathBuf,
+    root: PathBuf, // `dir` made absolute, some backends report absolute paths
+    events: Receiver<notify::Result<Event>>,
+    _watcher: RecommendedWatcher, // stops watching when dropped
+}
+
+impl AssetWatcher {
+    pub fn new(dir: &str) -> Option<Self> {
+        let dir = PathBuf::from(dir);
+        let (sender, events) = channel();
+        let mut watcher = match notify::recommended_watcher(sender) {
+            Ok(watcher) => watcher,
+            Err(err) => {
+                println!("Could not watch {}: {err}", dir.display());
+                return None;
+            }
+        };
+        if let Err(err) = watcher.watch(&dir, RecursiveMode::NonRecursive) {
+            println!("Could not watch {}: {err}", dir.display());
+            return None;
+        }
+        Some(Self {
+            root: fs::canonicalize(&dir).unwrap_or_else(|_| dir.clone()),
+            dir,
+            events,
+            _watcher: watcher,
+        })
+    }
+
+    /// Returns the files that changed since the last call, each once, as paths inside the
+    /// watched directory like `assets/level1.png`.
+    pub fn changed(&mut self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        for event in self.events.try_iter() {
+            match event {
+                Ok(event) if !event.kind.is_access() => {
+                    changed.extend(event.paths.iter().map(|path| self.relative(path)));
+                }
+                Ok(_) => {}
+                Err(err) => println!("Could not watch {}: {err}", self.dir.display()),
+            }
+        }
+        changed.sort();
+        changed.dedup();
+        changed
+    }
+
+    fn relative(&self, path: &Path) -> PathBuf {
+        match path.strip_prefix(&self.root) {
+            Ok(name) => self.dir.join(name),
+            Err(_) => path.to_path_buf(),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use raylib::math::Rectangle;
+
+use crate::{event::GameEvent, player::Player, GameState, Vector2i, TILE_SIZE_PIXELS};
+
+static PLATE_SCALE: f32 = 1.5; // minimum player scale that holds a pressure plate down
+static FRAGILE_SCALE: f32 = 1.5; // minimum player scale that breaks fragile floors
+static CRUMBLE_TICKS: i32 = 30; // how long a cracked floor holds before it gives way
+
+/// Presses and releases pressure plates, firing the level's trigger hook like a lever flip.
+pub fn update_pressure_plates(game_state: &mut GameState, player: &Player) {
+    let hitbox = player.hitbox();
+    let feet = Rectangle {
+        x: hitbox.x,
+        y: hitbox.y + hitbox.height - 1.0,
+        width: hitbox.width,
+        height: 1.0,
+    };
+    let heavy = !player.is_dead() && player.scale() >= PLATE_SCALE;
+
+    let mut changes: Vec<(Vector2i, usize)> = Vec::new();
+    for (pos, tile) in game_state.current_level.tilemap.iter() {
+        if tile.id() != 27 && tile.id() != 28 {
+            continue;
+        }
+        let plate = Rectangle {
+            x: (pos.x * TILE_SIZE_PIXELS) as f32,
+            y: (pos.y * TILE_SIZE_PIXELS) as f32,
+            width: TILE_SIZE_PIXELS as f32,
+            height: TILE_SIZE_PIXELS as f32,
+        };
+        let pressed = heavy && plate.check_collision_recs(&feet);
+        if pressed && tile.id() == 27 {
+            changes.push((pos.clone(), 28));
+        } else if !pressed && tile.id() == 28 {
+            changes.push((pos.clone(), 27));
+        }
+    }
+    for (pos, id) in changes {
+        game_state.current_level.tilemap.set_tile(pos.clone(), id);
+        game_state
+            .events
+            .push(GameEvent::LeverFlipped { x: pos.x, y: pos.y });
+    }
+}
+
+/// Cracks fragile floors under a heavy player and drops them once they've crumbled.
+pub fn update_fragile_floors(game_state: &mut GameState, player: &Player) {
+    game_state.crumbling.retain_mut(|(pos, timer)| {
+        *timer -= 1;
+        if *timer <= 0 {
+            game_state.current_level.tilemap.set_tile(pos.clone(), 0);
+            game_state.events.push(GameEvent::TileDestroyed {
+                position: pos.clone(),
+            });
+            return false;
+        }
+        true
+    });
+
+    if player.is_dead() || !player.on_ground || player.scale() < FRAGILE_SCALE {
+        return;
+    }
+    let hitbox = player.hitbox();
+    let below = Rectangle {
+        x: hitbox.x,
+        y: hitbox.y + hitbox.height,
+        width: hitbox.width,
+        height: 2.0,
+    };
+    let cracked: Vec<Vector2i> = game_state
+        .current_level
+        .tilemap
+        .overlapping(&below)
+        .filter(|(_pos, tile)| tile.id() == 29)
+        .map(|(pos, _tile)| pos.clone())
+        .collect();
+    for pos in cracked {
+        game_state.current_level.tilemap.set_tile(pos.clone(), 30);
+        game_state.crumbling.push((pos, CRUMBLE_TICKS));
+    }
+}